@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+const NOT_IN_ALPHABET: u8 = u8::MAX;
+
+/// The set of letters a dictionary is made of.
+///
+/// Every letter accepted by an alphabet maps to a slot index in `0..len()`.
+/// Upper case letters share the slot of their lower case form, and with accent
+/// folding enabled accented letters share the slot of their base letter, so
+/// "café" is stored and matched as "cafe". The final sigma "ς" always shares
+/// the slot of "σ", as the capital "Σ" stands for both.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alphabet {
+    letters: Vec<char>,
+    fold_accents: bool,
+    ascii_indices: [u8; 128],
+    indices: HashMap<char, usize>,
+}
+
+/// Returns the base letter of a lower case accented letter, or the letter itself.
+/// Letters such as "ñ" and "ß" are letters in their own right and never fold.
+pub(crate) fn fold_accent(letter: char) -> char {
+    match letter {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => 'a',
+        'ç' | 'ć' | 'č' => 'c',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'ń' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ō' | 'ø' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ś' | 'š' => 's',
+        'ź' | 'ż' | 'ž' => 'z',
+        'ά' => 'α',
+        'έ' => 'ε',
+        'ή' => 'η',
+        'ί' | 'ϊ' | 'ΐ' => 'ι',
+        'ό' => 'ο',
+        'ύ' | 'ϋ' | 'ΰ' => 'υ',
+        'ώ' => 'ω',
+        _ => letter,
+    }
+}
+
+fn to_lowercase(letter: char) -> char {
+    letter.to_lowercase().next().unwrap_or(letter)
+}
+
+impl Alphabet {
+    /// Creates an alphabet from its letters, in slot order. Letters are
+    /// lower cased and duplicates are ignored.
+    pub fn new(letters: &str) -> Alphabet {
+        let mut unique = Vec::new();
+        for letter in letters.chars().map(to_lowercase) {
+            if !unique.contains(&letter) {
+                unique.push(letter);
+            }
+        }
+        Alphabet::from_letters(unique, false)
+    }
+
+    pub fn english() -> Alphabet {
+        Alphabet::new("abcdefghijklmnopqrstuvwxyz")
+    }
+
+    pub fn spanish() -> Alphabet {
+        Alphabet::new("abcdefghijklmnñopqrstuvwxyzáéíóúü")
+    }
+
+    pub fn german() -> Alphabet {
+        Alphabet::new("abcdefghijklmnopqrstuvwxyzäöüß")
+    }
+
+    pub fn french() -> Alphabet {
+        Alphabet::new("abcdefghijklmnopqrstuvwxyzàâæçéèêëîïôœùûüÿ")
+    }
+
+    pub fn greek() -> Alphabet {
+        Alphabet::new("αβγδεζηθικλμνξοπρστυφχψωάέήίόύώϊϋΐΰ")
+    }
+
+    /// Makes accented letters interchangeable with their base letter whenever
+    /// the base letter is part of the alphabet.
+    pub fn with_accent_folding(self) -> Alphabet {
+        let letters = self
+            .letters
+            .iter()
+            .copied()
+            .filter(|&letter| {
+                let base = fold_accent(letter);
+                base == letter || !self.letters.contains(&base)
+            })
+            .collect();
+        Alphabet::from_letters(letters, true)
+    }
+
+    fn from_letters(letters: Vec<char>, fold_accents: bool) -> Alphabet {
+        let mut ascii_indices = [NOT_IN_ALPHABET; 128];
+        let mut indices = HashMap::new();
+
+        for (index, &letter) in letters.iter().enumerate() {
+            indices.insert(letter, index);
+            let mut upper_case = letter.to_uppercase();
+            if let (Some(upper), None) = (upper_case.next(), upper_case.next()) {
+                indices.entry(upper).or_insert(index);
+            }
+        }
+        if let Some(&index) = indices.get(&'σ') {
+            indices.entry('ς').or_insert(index);
+        }
+        if fold_accents {
+            // Accented letters whose base letter was kept resolve to its slot
+            for letter in ('\u{c0}'..='\u{24f}').chain('\u{386}'..='\u{3ce}') {
+                let base = fold_accent(to_lowercase(letter));
+                if let (None, Some(&index)) = (indices.get(&letter), indices.get(&base)) {
+                    indices.insert(letter, index);
+                }
+            }
+        }
+        for (&letter, &index) in indices.iter() {
+            if letter.is_ascii() && index < NOT_IN_ALPHABET as usize {
+                ascii_indices[letter as usize] = index as u8;
+            }
+        }
+
+        Alphabet {
+            letters,
+            fold_accents,
+            ascii_indices,
+            indices,
+        }
+    }
+
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    pub fn folds_accents(&self) -> bool {
+        self.fold_accents
+    }
+
+    /// Returns the slot of `letter`, or `None` if it is not part of the alphabet.
+    pub fn index_of(&self, letter: char) -> Option<usize> {
+        if letter.is_ascii() {
+            match self.ascii_indices[letter as usize] {
+                NOT_IN_ALPHABET => None,
+                index => Some(index as usize),
+            }
+        } else {
+            self.indices
+                .get(&letter)
+                .or_else(|| self.indices.get(&to_lowercase(letter)))
+                .copied()
+        }
+    }
+
+    /// Returns the letter `letter` is stored as, or `None` if it is not part of the alphabet.
+    pub fn normalize(&self, letter: char) -> Option<char> {
+        self.index_of(letter).map(|index| self.letters[index])
+    }
+
+    /// Returns the word as it is stored, or `None` if any letter is not part of the alphabet.
+    pub fn normalize_word(&self, word: &str) -> Option<String> {
+        word.chars().map(|letter| self.normalize(letter)).collect()
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Alphabet {
+        Alphabet::english()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_english_alphabet() {
+        let alphabet = Alphabet::english();
+
+        assert_eq!(alphabet.len(), 26);
+        assert_eq!(alphabet.index_of('a'), Some(0));
+        assert_eq!(alphabet.index_of('Z'), Some(25));
+        assert_eq!(alphabet.index_of('é'), None);
+        assert_eq!(alphabet.index_of('-'), None);
+        assert_eq!(alphabet.normalize_word("Apple"), Some("apple".to_owned()));
+        assert_eq!(alphabet.normalize_word("café"), None);
+    }
+
+    #[test]
+    fn test_unicode_alphabets() {
+        let spanish = Alphabet::spanish();
+        assert_eq!(spanish.normalize_word("NIÑO"), Some("niño".to_owned()));
+        assert_ne!(spanish.index_of('ñ'), spanish.index_of('n'));
+
+        let german = Alphabet::german();
+        assert_eq!(german.normalize_word("Straße"), Some("straße".to_owned()));
+        assert_eq!(german.normalize_word("ÄPFEL"), Some("äpfel".to_owned()));
+
+        // Upper case words have no final sigma, so both forms are stored as "σ"
+        let greek = Alphabet::greek();
+        assert_eq!(greek.normalize_word("ΛΌΓΟΣ"), Some("λόγοσ".to_owned()));
+        assert_eq!(greek.normalize_word("λόγος"), Some("λόγοσ".to_owned()));
+        assert_eq!(greek.index_of('ς'), greek.index_of('Σ'));
+    }
+
+    #[test]
+    fn test_accent_folding() {
+        let french = Alphabet::french().with_accent_folding();
+
+        assert!(french.folds_accents());
+        assert_eq!(french.normalize_word("Café"), Some("cafe".to_owned()));
+        assert_eq!(french.index_of('è'), french.index_of('e'));
+        assert_eq!(french.index_of('Ê'), french.index_of('e'));
+        // Letters without a base letter in the alphabet keep their own slot
+        assert_eq!(french.normalize('œ'), Some('œ'));
+        assert!(!french.letters().contains(&'é'));
+
+        let greek = Alphabet::greek().with_accent_folding();
+        assert_eq!(greek.normalize_word("λόγος"), Some("λογοσ".to_owned()));
+    }
+}
@@ -0,0 +1,429 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use memmap2::Mmap;
+use crate::dictionaries::alphabet::Alphabet;
+use crate::dictionaries::Dictionary;
+use crate::dictionaries::tries::dawg::Dawg;
+use crate::error::Error;
+
+// Layout of a compiled dictionary, all integers little endian:
+//
+//   header   magic (8) | version u32 | flags u32 | node count u32 | edge count u32
+//            | letter count u32 | reserved u32 | checksum u64
+//   letters  letter u32, the alphabet in slot order
+//   nodes    first edge u32 | edge count u32, with END_OF_WORD set on word endings
+//   edges    letter u32 | target node u32, sorted by letter within a node
+//
+// The checksum is a 64-bit FNV-1a hash of everything after the header.
+const MAGIC: &[u8; 8] = b"WFDAWG\0\0";
+const VERSION: u32 = 2;
+const HEADER_SIZE: usize = 40;
+const LETTER_SIZE: usize = 4;
+const NODE_SIZE: usize = 8;
+const EDGE_SIZE: usize = 8;
+const END_OF_WORD: u32 = 1 << 31;
+const FOLD_ACCENTS: u32 = 1;
+const ROOT: u32 = 0;
+
+pub(crate) const COMPILED_DICTIONARY_EXTENSION: &str = "dawg";
+
+/// A dictionary answered directly from the bytes of a memory-mapped file
+/// written by [`compile`], without rebuilding any nodes.
+pub(crate) struct CompiledDictionary {
+    bytes: Mmap,
+    alphabet: Alphabet,
+    node_count: u32,
+    nodes_offset: usize,
+    edges_offset: usize,
+}
+
+/// The parts of a validated compiled dictionary that are not read lazily.
+struct Layout {
+    alphabet: Alphabet,
+    node_count: u32,
+    nodes_offset: usize,
+    edges_offset: usize,
+}
+
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn to_bytes(dawg: &Dawg) -> Vec<u8> {
+    let alphabet = dawg.alphabet();
+    let letters: Vec<u8> = alphabet
+        .letters()
+        .iter()
+        .flat_map(|&letter| (letter as u32).to_le_bytes())
+        .collect();
+    let flags = if alphabet.folds_accents() { FOLD_ACCENTS } else { 0 };
+
+    let node_count = dawg.node_count() as u32;
+    let mut nodes = Vec::with_capacity(node_count as usize * NODE_SIZE);
+    let mut edges = Vec::new();
+    let mut edge_count = 0u32;
+
+    for node in 0..node_count {
+        let node_edges = dawg.edges(node);
+        let mut node_flags = node_edges.len() as u32;
+        if dawg.is_end_of_word(node) {
+            node_flags |= END_OF_WORD;
+        }
+        nodes.extend_from_slice(&edge_count.to_le_bytes());
+        nodes.extend_from_slice(&node_flags.to_le_bytes());
+
+        for &(letter, target) in node_edges {
+            edges.extend_from_slice(&(letter as u32).to_le_bytes());
+            edges.extend_from_slice(&target.to_le_bytes());
+        }
+        edge_count += node_edges.len() as u32;
+    }
+
+    let letter_count = alphabet.len() as u32;
+    let payload = [letters, nodes, edges].concat();
+    [
+        &MAGIC[..],
+        &VERSION.to_le_bytes(),
+        &flags.to_le_bytes(),
+        &node_count.to_le_bytes(),
+        &edge_count.to_le_bytes(),
+        &letter_count.to_le_bytes(),
+        &0u32.to_le_bytes(),
+        &checksum(&payload).to_le_bytes(),
+        &payload,
+    ]
+    .concat()
+}
+
+/// Validates the header, checksum and node table of a compiled dictionary
+/// and reads its alphabet, so that lookups can trust every edge range.
+fn validate(bytes: &[u8]) -> Result<Layout, Error> {
+    if bytes.len() < HEADER_SIZE || &bytes[..8] != MAGIC {
+        return Err(Error::UnsupportedFormat(String::from("file is not a compiled dictionary")));
+    }
+    let version = read_u32(bytes, 8);
+    if version != VERSION {
+        return Err(Error::UnsupportedFormat(format!("compiled dictionary version {}", version)));
+    }
+
+    let flags = read_u32(bytes, 12);
+    let node_count = read_u32(bytes, 16);
+    let edge_count = read_u32(bytes, 20);
+    let letter_count = read_u32(bytes, 24);
+    let nodes_offset = HEADER_SIZE + letter_count as usize * LETTER_SIZE;
+    let edges_offset = nodes_offset + node_count as usize * NODE_SIZE;
+    if node_count == 0 || bytes.len() != edges_offset + edge_count as usize * EDGE_SIZE {
+        return Err(Error::CorruptDictionary("file is truncated"));
+    }
+
+    let expected = u64::from_le_bytes(bytes[32..40].try_into().unwrap());
+    if checksum(&bytes[HEADER_SIZE..]) != expected {
+        return Err(Error::CorruptDictionary("checksum mismatch"));
+    }
+
+    for node in 0..node_count as usize {
+        let node_offset = nodes_offset + node * NODE_SIZE;
+        let first_edge = read_u32(bytes, node_offset) as u64;
+        let node_edge_count = (read_u32(bytes, node_offset + 4) & !END_OF_WORD) as u64;
+        if first_edge + node_edge_count > edge_count as u64 {
+            return Err(Error::CorruptDictionary("edge range out of bounds"));
+        }
+        let mut previous_letter = None;
+        for edge in first_edge..first_edge + node_edge_count {
+            let edge_offset = edges_offset + edge as usize * EDGE_SIZE;
+            let letter = read_u32(bytes, edge_offset);
+            if previous_letter.is_some_and(|previous| previous >= letter) {
+                return Err(Error::CorruptDictionary("edges are not sorted by letter"));
+            }
+            if read_u32(bytes, edge_offset + 4) >= node_count {
+                return Err(Error::CorruptDictionary("edge target out of bounds"));
+            }
+            previous_letter = Some(letter);
+        }
+    }
+
+    let letters = (HEADER_SIZE..nodes_offset)
+        .step_by(LETTER_SIZE)
+        .map(|offset| char::from_u32(read_u32(bytes, offset)).ok_or(Error::CorruptDictionary("invalid letter")))
+        .collect::<Result<String, Error>>()?;
+    let mut alphabet = Alphabet::new(&letters);
+    if flags & FOLD_ACCENTS != 0 {
+        alphabet = alphabet.with_accent_folding();
+    }
+
+    Ok(Layout {
+        alphabet,
+        node_count,
+        nodes_offset,
+        edges_offset,
+    })
+}
+
+/// Serializes `dawg` into the compiled dictionary format at `path`.
+pub(crate) fn compile(dawg: &Dawg, path: &str) -> Result<(), Error> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(&to_bytes(dawg))?;
+    writer.flush()?;
+    Ok(())
+}
+
+impl CompiledDictionary {
+    pub(crate) fn open(path: &str) -> Result<CompiledDictionary, Error> {
+        let file_path = Path::new(path);
+        if !file_path.exists() {
+            return Err(Error::FileNotFound(path.to_owned()));
+        }
+        let file = File::open(file_path)?;
+        // The mapping is read-only; the file is not expected to change while it is in use.
+        let bytes = unsafe { Mmap::map(&file) }?;
+
+        let layout = validate(&bytes)?;
+
+        Ok(CompiledDictionary {
+            bytes,
+            alphabet: layout.alphabet,
+            node_count: layout.node_count,
+            nodes_offset: layout.nodes_offset,
+            edges_offset: layout.edges_offset,
+        })
+    }
+
+    fn child(&self, node: u32, letter: char) -> Option<u32> {
+        let node_offset = self.nodes_offset + node as usize * NODE_SIZE;
+        let first_edge = read_u32(&self.bytes, node_offset) as usize;
+        let edge_count = (read_u32(&self.bytes, node_offset + 4) & !END_OF_WORD) as usize;
+
+        let (mut low, mut high) = (first_edge, first_edge + edge_count);
+        while low < high {
+            let middle = (low + high) / 2;
+            let edge_offset = self.edges_offset + middle * EDGE_SIZE;
+            let edge_letter = read_u32(&self.bytes, edge_offset);
+
+            if edge_letter == letter as u32 {
+                let target = read_u32(&self.bytes, edge_offset + 4);
+                return (target < self.node_count).then_some(target);
+            } else if edge_letter < letter as u32 {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        None
+    }
+
+    fn is_end_of_word(&self, node: u32) -> bool {
+        let node_offset = self.nodes_offset + node as usize * NODE_SIZE;
+        read_u32(&self.bytes, node_offset + 4) & END_OF_WORD != 0
+    }
+
+    fn find_node(&self, word: &str) -> Option<u32> {
+        word.chars().try_fold(ROOT, |node, letter| {
+            self.child(node, self.alphabet.normalize(letter)?)
+        })
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+impl Dictionary for CompiledDictionary {
+    type Node<'a> = u32;
+
+    fn search(&self, word: &str) -> bool {
+        self.find_node(word).is_some_and(|node| self.is_end_of_word(node))
+    }
+
+    fn starts_with(&self, prefix: &str) -> bool {
+        self.find_node(prefix).is_some()
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn root(&self) -> u32 {
+        ROOT
+    }
+
+    fn next(&self, node: &u32, letter: char) -> Option<u32> {
+        self.child(*node, letter)
+    }
+
+    fn is_word(&self, node: &u32) -> bool {
+        self.is_end_of_word(*node)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs;
+    use std::time::Instant;
+    use crate::SCRABBLE_DICTIONARY_PATH;
+    use crate::dictionaries::tries::Trie;
+    use crate::dictionaries::tries::trie_builder::{TrieBuilder, TxtFileTrieBuilder};
+
+    fn temp_path(name: &str) -> String {
+        temp_dir()
+            .join(format!("{}-{}.{}", name, std::process::id(), COMPILED_DICTIONARY_EXTENSION))
+            .to_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    fn small_dawg() -> Dawg {
+        let mut dawg = Dawg::new();
+        for word in ["app", "apple", "ball", "banana", "bat"] {
+            dawg.insert(word).unwrap();
+        }
+        dawg.finish();
+        dawg
+    }
+
+    #[test]
+    fn test_compiled_dictionary_round_trip() {
+        let path = temp_path("round-trip");
+        compile(&small_dawg(), &path).unwrap();
+        let dictionary = CompiledDictionary::open(&path).unwrap();
+
+        assert!(dictionary.search("apple"));
+        assert!(dictionary.search("app"));
+        assert!(dictionary.search("Banana"));
+        assert!(!dictionary.search("ban"));
+        assert!(dictionary.starts_with("ba"));
+        assert!(!dictionary.starts_with("ballistic"));
+        assert!(!dictionary.starts_with("cackle"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_compiled_dictionary_keeps_alphabet() {
+        let mut dawg = Dawg::with_alphabet(Alphabet::spanish().with_accent_folding());
+        for word in ["año", "canción"] {
+            dawg.insert(word).unwrap();
+        }
+        dawg.finish();
+        let path = temp_path("alphabet");
+        compile(&dawg, &path).unwrap();
+        let dictionary = CompiledDictionary::open(&path).unwrap();
+
+        assert_eq!(dictionary.alphabet(), dawg.alphabet());
+        assert!(dictionary.search("cancion"));
+        assert!(dictionary.search("CANCIÓN"));
+        assert!(!dictionary.search("ano"));
+        assert!(dictionary.search("año"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_compiled_dictionary_validates_checksum() {
+        let mut bytes = to_bytes(&small_dawg());
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+
+        assert!(matches!(validate(&bytes), Err(Error::CorruptDictionary("checksum mismatch"))));
+    }
+
+    /// Rewrites the checksum after tampering with the payload.
+    fn with_checksum(mut bytes: Vec<u8>) -> Vec<u8> {
+        let checksum = checksum(&bytes[HEADER_SIZE..]);
+        bytes[32..40].copy_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_compiled_dictionary_validates_edge_ranges() {
+        let bytes = to_bytes(&small_dawg());
+        let nodes_offset = HEADER_SIZE + read_u32(&bytes, 24) as usize * LETTER_SIZE;
+
+        let mut corrupt = bytes.clone();
+        corrupt[nodes_offset + 4..nodes_offset + 8].copy_from_slice(&1000u32.to_le_bytes());
+        let result = validate(&with_checksum(corrupt));
+        assert!(matches!(result, Err(Error::CorruptDictionary("edge range out of bounds"))));
+
+        let mut corrupt = bytes.clone();
+        corrupt[nodes_offset..nodes_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let result = validate(&with_checksum(corrupt));
+        assert!(matches!(result, Err(Error::CorruptDictionary("edge range out of bounds"))));
+    }
+
+    #[test]
+    fn test_compiled_dictionary_validates_edge_order() {
+        // The root's edges, "a" then "b", are the first two in the file
+        let bytes = to_bytes(&small_dawg());
+        let nodes_offset = HEADER_SIZE + read_u32(&bytes, 24) as usize * LETTER_SIZE;
+        let edges_offset = nodes_offset + read_u32(&bytes, 16) as usize * NODE_SIZE;
+        let mut corrupt = bytes.clone();
+        corrupt[edges_offset..edges_offset + 4].copy_from_slice(&('c' as u32).to_le_bytes());
+
+        let result = validate(&with_checksum(corrupt));
+        assert!(matches!(result, Err(Error::CorruptDictionary("edges are not sorted by letter"))));
+        assert!(validate(&with_checksum(bytes)).is_ok());
+    }
+
+    #[test]
+    fn test_compiled_dictionary_validates_edge_targets() {
+        let bytes = to_bytes(&small_dawg());
+        let node_count = read_u32(&bytes, 16);
+        let nodes_offset = HEADER_SIZE + read_u32(&bytes, 24) as usize * LETTER_SIZE;
+        let edges_offset = nodes_offset + node_count as usize * NODE_SIZE;
+        let mut corrupt = bytes.clone();
+        corrupt[edges_offset + 4..edges_offset + 8].copy_from_slice(&node_count.to_le_bytes());
+
+        let result = validate(&with_checksum(corrupt));
+        assert!(matches!(result, Err(Error::CorruptDictionary("edge target out of bounds"))));
+    }
+
+    #[test]
+    fn test_compiled_dictionary_validates_version() {
+        let mut bytes = to_bytes(&small_dawg());
+        bytes[8..12].copy_from_slice(&1u32.to_le_bytes());
+
+        let error = validate(&bytes).err().unwrap();
+        assert_eq!(error.to_string(), "Unsupported dictionary format: compiled dictionary version 1");
+    }
+
+    #[test]
+    fn test_compiled_dictionary_validates_length() {
+        let bytes = to_bytes(&small_dawg());
+
+        let result = validate(&bytes[..bytes.len() - EDGE_SIZE]);
+        assert!(matches!(result, Err(Error::CorruptDictionary("file is truncated"))));
+    }
+
+    #[test]
+    fn test_compiled_dictionary_validates_magic() {
+        let result = CompiledDictionary::open(SCRABBLE_DICTIONARY_PATH);
+        assert!(matches!(result, Err(Error::UnsupportedFormat(_))));
+
+        let result = CompiledDictionary::open("inexistent.dawg");
+        assert!(matches!(result, Err(Error::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_compiled_scrabble_dictionary() {
+        let mut dawg = Dawg::new();
+        TxtFileTrieBuilder::new(SCRABBLE_DICTIONARY_PATH).unwrap().build(&mut dawg).unwrap();
+        let path = temp_path("scrabble");
+        compile(&dawg, &path).unwrap();
+
+        let start_time = Instant::now();
+        let dictionary = CompiledDictionary::open(&path).unwrap();
+        println!("Loaded compiled dictionary in {:?}", Instant::now() - start_time);
+
+        for word in ["apple", "wizard", "zzz", "quixotic", "pelem", "aahing", "xylophones"] {
+            assert_eq!(dictionary.search(word), dawg.search(word), "{}", word);
+            assert_eq!(dictionary.starts_with(word), dawg.starts_with(word), "{}", word);
+        }
+
+        fs::remove_file(path).unwrap();
+    }
+}
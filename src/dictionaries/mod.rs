@@ -1,7 +1,93 @@
+use std::path::Path;
+use crate::dictionaries::alphabet::Alphabet;
+use crate::dictionaries::compiled::{CompiledDictionary, COMPILED_DICTIONARY_EXTENSION};
+use crate::dictionaries::tries::dawg::Dawg;
+use crate::dictionaries::tries::Trie;
+use crate::dictionaries::tries::trie_builder::{TrieBuilder, TxtFileTrieBuilder};
+use crate::error::Error;
+
 pub(crate) mod tries;
+pub(crate) mod compiled;
+pub(crate) mod alphabet;
 mod sets;
 
 pub(crate) trait Dictionary {
+    /// A position in the dictionary reached by following a prefix from the root.
+    type Node<'a>: Clone where Self: 'a;
+
     fn search(&self, word: &str) -> bool;
     fn starts_with(&self, prefix: &str) -> bool;
-}
\ No newline at end of file
+    fn alphabet(&self) -> &Alphabet;
+
+    fn root(&self) -> Self::Node<'_>;
+    /// Follows `letter`, which must already be normalized by the alphabet.
+    fn next<'a>(&'a self, node: &Self::Node<'a>, letter: char) -> Option<Self::Node<'a>>;
+    fn is_word(&self, node: &Self::Node<'_>) -> bool;
+}
+
+/// A dictionary loaded from either a `.txt` word list or a compiled file.
+pub(crate) enum LoadedDictionary {
+    Dawg(Dawg),
+    Compiled(CompiledDictionary),
+}
+
+impl LoadedDictionary {
+    /// Compiled dictionaries carry their own alphabet, so `alphabet` only
+    /// applies to word lists.
+    pub(crate) fn load(path: &str, alphabet: Alphabet) -> Result<LoadedDictionary, Error> {
+        let extension = Path::new(path).extension().and_then(|extension| extension.to_str());
+        if extension == Some(COMPILED_DICTIONARY_EXTENSION) {
+            Ok(LoadedDictionary::Compiled(CompiledDictionary::open(path)?))
+        } else {
+            let mut dawg = Dawg::with_alphabet(alphabet);
+            TxtFileTrieBuilder::new(path)?.build(&mut dawg)?;
+            Ok(LoadedDictionary::Dawg(dawg))
+        }
+    }
+}
+
+impl Dictionary for LoadedDictionary {
+    type Node<'a> = u32;
+
+    fn search(&self, word: &str) -> bool {
+        match self {
+            LoadedDictionary::Dawg(dawg) => dawg.search(word),
+            LoadedDictionary::Compiled(compiled) => compiled.search(word),
+        }
+    }
+
+    fn starts_with(&self, prefix: &str) -> bool {
+        match self {
+            LoadedDictionary::Dawg(dawg) => dawg.starts_with(prefix),
+            LoadedDictionary::Compiled(compiled) => compiled.starts_with(prefix),
+        }
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        match self {
+            LoadedDictionary::Dawg(dawg) => dawg.alphabet(),
+            LoadedDictionary::Compiled(compiled) => compiled.alphabet(),
+        }
+    }
+
+    fn root(&self) -> u32 {
+        match self {
+            LoadedDictionary::Dawg(dawg) => dawg.root(),
+            LoadedDictionary::Compiled(compiled) => compiled.root(),
+        }
+    }
+
+    fn next(&self, node: &u32, letter: char) -> Option<u32> {
+        match self {
+            LoadedDictionary::Dawg(dawg) => dawg.next(node, letter),
+            LoadedDictionary::Compiled(compiled) => compiled.next(node, letter),
+        }
+    }
+
+    fn is_word(&self, node: &u32) -> bool {
+        match self {
+            LoadedDictionary::Dawg(dawg) => dawg.is_word(node),
+            LoadedDictionary::Compiled(compiled) => compiled.is_word(node),
+        }
+    }
+}
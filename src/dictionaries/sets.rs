@@ -1,7 +1,11 @@
 use std::collections::HashSet;
+use std::sync::OnceLock;
+use crate::dictionaries::alphabet::Alphabet;
 use crate::dictionaries::Dictionary;
 
 impl Dictionary for HashSet<String> {
+    type Node<'a> = String;
+
     fn search(&self, word: &str) -> bool {
         self.contains(word)
     }
@@ -9,4 +13,22 @@ impl Dictionary for HashSet<String> {
     fn starts_with(&self, prefix: &str) -> bool {
         self.iter().any(|word| word.starts_with(prefix))
     }
-}
\ No newline at end of file
+
+    fn alphabet(&self) -> &Alphabet {
+        static ENGLISH: OnceLock<Alphabet> = OnceLock::new();
+        ENGLISH.get_or_init(Alphabet::english)
+    }
+
+    fn root(&self) -> String {
+        String::new()
+    }
+
+    fn next(&self, node: &String, letter: char) -> Option<String> {
+        let prefix = format!("{}{}", node, letter);
+        self.starts_with(&prefix).then_some(prefix)
+    }
+
+    fn is_word(&self, node: &String) -> bool {
+        self.contains(node)
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use crate::dictionaries::alphabet::Alphabet;
+use crate::dictionaries::Dictionary;
+use crate::dictionaries::tries::Trie;
+use crate::error::Error;
+
+/// A node of the word graph. Edges are kept sorted by letter so that lookups
+/// can binary search them and so that equal nodes compare equal in the register.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct DawgNode {
+    edges: Vec<(char, u32)>,
+    is_end_of_word: bool,
+}
+
+/// A directed acyclic word graph (minimal acyclic automaton).
+///
+/// Words must be inserted in lexicographic order, or `insert` returns an
+/// error. This lets the graph be minimized incrementally (Daciuk et al.) so
+/// common suffixes are shared as well as common prefixes.
+#[derive(Clone)]
+pub(crate) struct Dawg {
+    nodes: Vec<DawgNode>,
+    register: HashMap<DawgNode, u32>,
+    previous_word: String,
+    unchecked: Vec<(u32, char, u32)>,
+    alphabet: Alphabet,
+}
+
+const ROOT: u32 = 0;
+
+impl DawgNode {
+    fn new() -> DawgNode {
+        DawgNode {
+            edges: Vec::new(),
+            is_end_of_word: false,
+        }
+    }
+
+    fn child(&self, letter: char) -> Option<u32> {
+        self.edges
+            .binary_search_by_key(&letter, |&(edge_letter, _)| edge_letter)
+            .ok()
+            .map(|index| self.edges[index].1)
+    }
+}
+
+impl Dawg {
+    fn normalize(&self, word: &str) -> Result<String, Error> {
+        word.chars()
+            .map(|letter| self.alphabet.normalize(letter).ok_or(Error::NotInAlphabet(letter)))
+            .collect()
+    }
+
+    fn find_node(&self, word: &str) -> Option<&DawgNode> {
+        let mut current = &self.nodes[ROOT as usize];
+        for letter in word.chars() {
+            let letter = self.alphabet.normalize(letter)?;
+            current = &self.nodes[current.child(letter)? as usize];
+        }
+        Some(current)
+    }
+
+    /// Replaces every node on the unchecked path below `down_to` with an
+    /// equivalent registered node, registering it if it is new.
+    fn minimize(&mut self, down_to: usize) {
+        while self.unchecked.len() > down_to {
+            let (parent, letter, child) = self.unchecked.pop().unwrap();
+            let node = &self.nodes[child as usize];
+
+            if let Some(&existing) = self.register.get(node) {
+                let edges = &mut self.nodes[parent as usize].edges;
+                edges.last_mut().unwrap().1 = existing;
+                debug_assert_eq!(edges.last().unwrap().0, letter);
+
+                // Every descendant of a replaced node was itself replaced and
+                // dropped, so the replaced node is always the newest one.
+                debug_assert_eq!(child as usize, self.nodes.len() - 1);
+                self.nodes.pop();
+            } else {
+                self.register.insert(node.clone(), child);
+            }
+        }
+    }
+
+    pub(crate) fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub(crate) fn edges(&self, node: u32) -> &[(char, u32)] {
+        &self.nodes[node as usize].edges
+    }
+
+    pub(crate) fn is_end_of_word(&self, node: u32) -> bool {
+        self.nodes[node as usize].is_end_of_word
+    }
+
+    /// Approximate number of heap and inline bytes held by the graph,
+    /// including the register kept around for further inserts.
+    #[cfg(test)]
+    pub(crate) fn memory_usage(&self) -> usize {
+        let edge_size = size_of::<(char, u32)>();
+        let node_bytes: usize = self
+            .nodes
+            .iter()
+            .map(|node| size_of::<DawgNode>() + node.edges.capacity() * edge_size)
+            .sum();
+        let register_bytes: usize = self
+            .register
+            .keys()
+            .map(|node| size_of::<(DawgNode, u32)>() + node.edges.capacity() * edge_size)
+            .sum();
+
+        size_of::<Dawg>() + node_bytes + register_bytes
+    }
+}
+
+impl Trie for Dawg {
+    fn with_alphabet(alphabet: Alphabet) -> Dawg {
+        Dawg {
+            nodes: vec![DawgNode::new()],
+            register: HashMap::new(),
+            previous_word: String::new(),
+            unchecked: Vec::new(),
+            alphabet,
+        }
+    }
+
+    fn insert(&mut self, word: &str) -> Result<(), Error> {
+        let word = self.normalize(word)?;
+        if word < self.previous_word {
+            return Err(Error::UnsortedWords {
+                word,
+                previous: self.previous_word.clone(),
+            });
+        }
+        if word == self.previous_word && !word.is_empty() {
+            return Ok(());
+        }
+
+        let common_prefix = word
+            .chars()
+            .zip(self.previous_word.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        self.minimize(common_prefix);
+
+        let mut current = match self.unchecked.last() {
+            Some(&(_, _, child)) => child,
+            None => ROOT,
+        };
+        for letter in word.chars().skip(common_prefix) {
+            let child = self.nodes.len() as u32;
+            self.nodes.push(DawgNode::new());
+            self.nodes[current as usize].edges.push((letter, child));
+            self.unchecked.push((current, letter, child));
+            current = child;
+        }
+
+        self.nodes[current as usize].is_end_of_word = true;
+        self.previous_word = word;
+        Ok(())
+    }
+
+    fn finish(&mut self) {
+        self.minimize(0);
+    }
+}
+
+impl Dictionary for Dawg {
+    type Node<'a> = u32;
+
+    fn search(&self, word: &str) -> bool {
+        self.find_node(word).is_some_and(|node| node.is_end_of_word)
+    }
+
+    fn starts_with(&self, prefix: &str) -> bool {
+        self.find_node(prefix).is_some()
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn root(&self) -> u32 {
+        ROOT
+    }
+
+    fn next(&self, node: &u32, letter: char) -> Option<u32> {
+        self.nodes[*node as usize].child(letter)
+    }
+
+    fn is_word(&self, node: &u32) -> bool {
+        self.is_end_of_word(*node)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Instant;
+    use crate::SCRABBLE_DICTIONARY_PATH;
+    use crate::dictionaries::tries::trie::SimpleTrie;
+    use crate::dictionaries::tries::trie_builder::{TrieBuilder, TxtFileTrieBuilder};
+
+    #[test]
+    fn test_insert_into_dawg() {
+        let mut dawg = Dawg::new();
+        let words = ["app", "apple", "ball", "banana", "bat"];
+
+        for word in words {
+            dawg.insert(word).unwrap();
+        }
+
+        assert!(dawg.search("apple"));
+        assert!(dawg.search("app"));
+        assert!(dawg.search("banana"));
+        assert!(!dawg.search("ban"));
+        assert!(!dawg.search("b"));
+        assert!(dawg.search("BALL"));
+        assert!(dawg.starts_with("b"));
+        assert!(dawg.starts_with("ba"));
+        assert!(dawg.starts_with("ball"));
+        assert!(!dawg.starts_with("ballistic"));
+        assert!(!dawg.starts_with("cackle"));
+    }
+
+    #[test]
+    fn test_dawg_shares_suffixes() {
+        let mut dawg = Dawg::new();
+        for word in ["cats", "dogs", "hats", "logs"] {
+            dawg.insert(word).unwrap();
+        }
+        dawg.finish();
+
+        // root, "c"/"h", "d"/"l", "a", "o", a shared "t"/"g" node and the "s" ending
+        assert_eq!(dawg.node_count(), 7);
+        assert!(dawg.search("hats"));
+        assert!(!dawg.search("hogs"));
+        assert!(!dawg.search("cat"));
+    }
+
+    #[test]
+    fn test_dawg_with_accent_folding() {
+        let mut dawg = Dawg::with_alphabet(Alphabet::french().with_accent_folding());
+        for word in ["café", "cafés", "thé"] {
+            dawg.insert(word).unwrap();
+        }
+        dawg.finish();
+
+        assert!(dawg.search("cafe"));
+        assert!(dawg.search("CAFÉS"));
+        assert!(dawg.search("the"));
+        assert!(!dawg.search("caf"));
+        assert!(!dawg.starts_with("ß"));
+    }
+
+    #[test]
+    fn test_dawg_rejects_unsorted_input() {
+        let mut dawg = Dawg::new();
+        dawg.insert("banana").unwrap();
+
+        let error = dawg.insert("apple").unwrap_err();
+        assert!(matches!(&error, Error::UnsortedWords { word, previous } if word == "apple" && previous == "banana"));
+        assert_eq!(error.to_string(), "Words must be inserted in lexicographic order: apple after banana");
+        assert!(!dawg.search("apple"));
+    }
+
+    #[test]
+    fn test_dawg_compared_to_simple_trie() {
+        let txt_file_trie_builder = TxtFileTrieBuilder::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+
+        let start_time = Instant::now();
+        let mut simple_trie = SimpleTrie::new();
+        txt_file_trie_builder.build(&mut simple_trie).unwrap();
+        let simple_trie_time = Instant::now() - start_time;
+
+        let start_time = Instant::now();
+        let mut dawg = Dawg::new();
+        txt_file_trie_builder.build(&mut dawg).unwrap();
+        let dawg_time = Instant::now() - start_time;
+
+        println!(
+            "SimpleTrie: {} nodes, {} bytes, built in {:?}",
+            simple_trie.node_count(),
+            simple_trie.memory_usage(),
+            simple_trie_time
+        );
+        println!(
+            "Dawg: {} nodes, {} bytes, built in {:?}",
+            dawg.node_count(),
+            dawg.memory_usage(),
+            dawg_time
+        );
+
+        assert!(dawg.node_count() * 5 < simple_trie.node_count());
+        assert!(dawg.memory_usage() * 10 < simple_trie.memory_usage());
+
+        for word in ["apple", "wizard", "zzz", "quixotic", "pelem", "aahing", "xylophones"] {
+            assert_eq!(dawg.search(word), simple_trie.search(word), "{}", word);
+            assert_eq!(dawg.starts_with(word), simple_trie.starts_with(word), "{}", word);
+        }
+    }
+}
@@ -1,9 +1,19 @@
+use crate::dictionaries::alphabet::Alphabet;
 use crate::dictionaries::Dictionary;
+use crate::error::Error;
 
+#[cfg(test)]
 pub(crate) mod trie;
 pub(crate) mod trie_builder;
+pub(crate) mod dawg;
 
 pub(crate) trait Trie: Dictionary {
-    fn new() -> impl Dictionary;
-    fn insert(&mut self, word: &str);
+    fn new() -> Self where Self: Sized {
+        Self::with_alphabet(Alphabet::english())
+    }
+    fn with_alphabet(alphabet: Alphabet) -> Self where Self: Sized;
+    fn insert(&mut self, word: &str) -> Result<(), Error>;
+
+    /// Called once all words have been inserted.
+    fn finish(&mut self) {}
 }
\ No newline at end of file
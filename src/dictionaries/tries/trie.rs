@@ -1,10 +1,15 @@
+use crate::dictionaries::alphabet::Alphabet;
 use crate::dictionaries::Dictionary;
 use crate::dictionaries::tries::Trie;
+use crate::error::Error;
+
 macro_rules! search_for_chars {
-    ($prefix: expr, $current: expr) => {
+    ($trie: expr, $prefix: expr, $current: expr) => {
         for letter in $prefix.chars() {
-            let index = SimpleTrie::char_to_index(letter);
-            if let None = $current.children[index] {
+            let Ok(index) = $trie.char_to_index(letter) else {
+                return false;
+            };
+            if $current.children[index].is_none() {
                 return false;
             }
             $current = $current.children[index].as_deref().unwrap();
@@ -13,67 +18,106 @@ macro_rules! search_for_chars {
 }
 
 #[derive(Clone)]
-struct TrieNode {
-    children: [Option<Box<TrieNode>>; 26],
+pub(crate) struct TrieNode {
+    children: Box<[Option<Box<TrieNode>>]>,
     is_end_of_word: bool,
 }
 
 #[derive(Clone)]
 pub(crate) struct SimpleTrie {
     root: TrieNode,
+    alphabet: Alphabet,
 }
 
 impl TrieNode {
-    fn new() -> TrieNode {
+    fn new(size: usize) -> TrieNode {
         TrieNode {
-            children: Default::default(),
+            children: (0..size).map(|_| None).collect(),
             is_end_of_word: false,
         }
     }
 }
 
 impl SimpleTrie {
-    fn char_to_index(letter: char) -> usize {
-        if !letter.is_alphabetic() {
-            panic!("Character {} is not an alphabet", letter);
+    fn char_to_index(&self, letter: char) -> Result<usize, Error> {
+        self.alphabet.index_of(letter).ok_or(Error::NotInAlphabet(letter))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend(node.children.iter().flatten().map(|child| child.as_ref()));
         }
-        letter.to_lowercase().next().unwrap() as usize - 'a' as usize
+        count
+    }
+
+    /// Approximate number of bytes held by the trie, one boxed node per prefix.
+    #[cfg(test)]
+    pub(crate) fn memory_usage(&self) -> usize {
+        let slots = self.alphabet.len() * size_of::<Option<Box<TrieNode>>>();
+        self.node_count() * (size_of::<TrieNode>() + slots)
     }
 }
 
 impl Trie for SimpleTrie {
-    fn new() -> SimpleTrie {
+    fn with_alphabet(alphabet: Alphabet) -> SimpleTrie {
         SimpleTrie {
-            root: TrieNode::new(),
+            root: TrieNode::new(alphabet.len()),
+            alphabet,
         }
     }
-    fn insert(&mut self, word: &str) {
+    fn insert(&mut self, word: &str) -> Result<(), Error> {
+        let size = self.alphabet.len();
+        let indices = word
+            .chars()
+            .map(|letter| self.char_to_index(letter))
+            .collect::<Result<Vec<usize>, Error>>()?;
         let mut current = &mut self.root;
 
-        for letter in word.chars() {
-            let index = SimpleTrie::char_to_index(letter);
-            if let None = current.children[index] {
-                current.children[index] = Some(Box::from(TrieNode::new()));
+        for index in indices {
+            if current.children[index].is_none() {
+                current.children[index] = Some(Box::from(TrieNode::new(size)));
             }
             current = current.children[index].as_deref_mut().unwrap();
         }
         current.is_end_of_word = true;
+        Ok(())
     }
 }
 
 impl Dictionary for SimpleTrie {
+    type Node<'a> = &'a TrieNode;
 
     fn search(&self, word: &str) -> bool {
         let mut current = &self.root;
-        search_for_chars!(word, current);
+        search_for_chars!(self, word, current);
         current.is_end_of_word
     }
 
     fn starts_with(&self, prefix: &str) -> bool {
         let mut current = &self.root;
-        search_for_chars!(prefix, current);
+        search_for_chars!(self, prefix, current);
         true
     }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn root(&self) -> &TrieNode {
+        &self.root
+    }
+
+    fn next<'a>(&'a self, node: &&'a TrieNode, letter: char) -> Option<&'a TrieNode> {
+        node.children[self.alphabet.index_of(letter)?].as_deref()
+    }
+
+    fn is_word(&self, node: &&TrieNode) -> bool {
+        node.is_end_of_word
+    }
 }
 
 #[cfg(test)]
@@ -86,7 +130,7 @@ mod test {
         let words = ["apple", "app", "banana", "bat", "ball"];
 
         for word in words {
-            trie.insert(word);
+            trie.insert(word).unwrap();
         }
 
         assert!(trie.search("apple"));
@@ -101,4 +145,34 @@ mod test {
         assert!(!trie.starts_with("ballistic"));
         assert!(!trie.starts_with("cackle"));
     }
+
+    #[test]
+    fn test_insert_into_trie_with_alphabet() {
+        let mut trie = SimpleTrie::with_alphabet(Alphabet::spanish());
+        for word in ["año", "canción", "pingüino"] {
+            trie.insert(word).unwrap();
+        }
+
+        assert!(trie.search("AÑO"));
+        assert!(trie.search("canción"));
+        assert!(!trie.search("ano"));
+        assert!(!trie.search("cancion"));
+        assert!(trie.starts_with("pingü"));
+
+        let mut trie = SimpleTrie::with_alphabet(Alphabet::french().with_accent_folding());
+        trie.insert("café").unwrap();
+
+        assert!(trie.search("cafe"));
+        assert!(trie.search("CAFÉ"));
+        assert!(trie.search("cafè"));
+    }
+
+    #[test]
+    fn test_insert_into_trie_validates_alphabet() {
+        let mut trie = SimpleTrie::new();
+
+        assert!(matches!(trie.insert("straße"), Err(Error::NotInAlphabet('ß'))));
+        assert!(!trie.starts_with("stra"));
+        assert!(!trie.search("straße"));
+    }
 }
\ No newline at end of file
@@ -2,38 +2,101 @@ use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 use crate::dictionaries::tries::Trie;
+use crate::error::Error;
 
 pub(crate) trait TrieBuilder {
-    fn build<T: Trie>(&self, trie: &mut T);
+    fn build<T: Trie>(&self, trie: &mut T) -> Result<(), Error>;
 }
 
 pub(crate) struct TxtFileTrieBuilder<'a> {
     file_path: &'a Path,
 }
 
+/// Builds a trie from words held in memory, such as a puzzle's word list.
+/// Spaces and hyphens are dropped, so "ICE CREAM" is inserted as "icecream".
+pub(crate) struct WordListTrieBuilder<'a> {
+    words: &'a [&'a str],
+}
+
+/// Inserts already normalized words in sorted order, which the DAWG needs.
+fn insert_sorted<T: Trie>(trie: &mut T, mut words: Vec<String>) -> Result<(), Error> {
+    words.sort_unstable();
+    words.dedup();
+    for word in words {
+        trie.insert(&word)?;
+    }
+    trie.finish();
+    Ok(())
+}
+
+/// Normalizes `word` with the trie's alphabet, reporting the first letter
+/// outside of it along with `line`.
+fn normalize<T: Trie>(trie: &T, word: &str, line: usize) -> Result<String, Error> {
+    trie.alphabet().normalize_word(word).ok_or_else(|| {
+        let character = word
+            .chars()
+            .find(|&letter| trie.alphabet().index_of(letter).is_none())
+            .unwrap_or_default();
+        Error::InvalidCharacter { character, line }
+    })
+}
+
 impl TxtFileTrieBuilder<'_> {
-    pub(crate) fn new(path: &'_ str) -> TxtFileTrieBuilder {
+    pub(crate) fn new(path: &'_ str) -> Result<TxtFileTrieBuilder<'_>, Error> {
         let file_path = Path::new(path);
         if !file_path.exists() {
-            panic!("File path does not exist: {}", path);
+            return Err(Error::FileNotFound(path.to_owned()));
         }
-        if file_path.extension().unwrap() != "txt" {
-            panic!("File is not a txt file")
+        if file_path.extension().is_none_or(|extension| extension != "txt") {
+            return Err(Error::UnsupportedFormat(format!("{} is not a txt file", path)));
         }
-        TxtFileTrieBuilder { file_path }
+        Ok(TxtFileTrieBuilder { file_path })
     }
 }
 
 impl TrieBuilder for TxtFileTrieBuilder<'_> {
-    fn build<T: Trie>(&self, trie: &mut T) {
-        let file = File::open(self.file_path).expect("Could not open file");
+    /// Words are normalized with the trie's alphabet and inserted in sorted
+    /// order, whatever collation the file itself was sorted with.
+    fn build<T: Trie>(&self, trie: &mut T) -> Result<(), Error> {
+        let file = File::open(self.file_path)?;
         let bufreader = BufReader::new(file);
+        let mut words = Vec::new();
 
-        for line in bufreader.lines() {
-            if let Ok(word) = line {
-                trie.insert(&word);
+        for (index, line) in bufreader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
             }
+            words.push(normalize(trie, line, index + 1)?);
         }
+
+        insert_sorted(trie, words)
+    }
+}
+
+impl WordListTrieBuilder<'_> {
+    pub(crate) fn new<'a>(words: &'a [&'a str]) -> WordListTrieBuilder<'a> {
+        WordListTrieBuilder { words }
+    }
+}
+
+impl TrieBuilder for WordListTrieBuilder<'_> {
+    /// Invalid characters are reported with the word's position in the list as its line.
+    fn build<T: Trie>(&self, trie: &mut T) -> Result<(), Error> {
+        let words = self
+            .words
+            .iter()
+            .enumerate()
+            .map(|(index, word)| {
+                let word: String = word.chars().filter(|&letter| letter != ' ' && letter != '-').collect();
+                (index, word)
+            })
+            .filter(|(_, word)| !word.is_empty())
+            .map(|(index, word)| normalize(trie, &word, index + 1))
+            .collect::<Result<Vec<String>, Error>>()?;
+
+        insert_sorted(trie, words)
     }
 }
 
@@ -42,29 +105,74 @@ mod test {
     use crate::dictionaries::Dictionary;
     use crate::SCRABBLE_DICTIONARY_PATH;
     use super::*;
+    use crate::dictionaries::alphabet::Alphabet;
     use crate::dictionaries::tries::trie::SimpleTrie;
+    use crate::dictionaries::tries::dawg::Dawg;
 
     #[test]
-    #[should_panic(expected = "File path does not exist: inexistent.txt")]
     fn txt_trie_builder_validates_file_path() {
-        TxtFileTrieBuilder::new("inexistent.txt");
+        let result = TxtFileTrieBuilder::new("inexistent.txt");
+
+        assert!(matches!(result, Err(Error::FileNotFound(path)) if path == "inexistent.txt"));
     }
 
     #[test]
-    #[should_panic(expected = "File is not a txt file")]
     fn txt_trie_new_validates_file_extension() {
-        TxtFileTrieBuilder::new("README.md");
+        assert!(matches!(TxtFileTrieBuilder::new("README.md"), Err(Error::UnsupportedFormat(_))));
+        assert!(matches!(TxtFileTrieBuilder::new("src"), Err(Error::UnsupportedFormat(_))));
     }
 
     #[test]
     fn txt_trie_builder_builds_trie() {
-        let txt_file_trie_builder = TxtFileTrieBuilder::new(SCRABBLE_DICTIONARY_PATH);
+        let txt_file_trie_builder = TxtFileTrieBuilder::new(SCRABBLE_DICTIONARY_PATH).unwrap();
         let mut simple_trie = SimpleTrie::new();
-        txt_file_trie_builder.build(&mut simple_trie);
+        txt_file_trie_builder.build(&mut simple_trie).unwrap();
 
         assert!(simple_trie.search("apple"));
         assert!(simple_trie.search("wizard"));
         assert!(!simple_trie.search("pelem"));
         assert!(simple_trie.starts_with("zz"));
     }
+
+    #[test]
+    fn word_list_trie_builder_builds_trie() {
+        let mut dawg = Dawg::new();
+        WordListTrieBuilder::new(&["Wizard", "apple", "apple", "ZOO"]).build(&mut dawg).unwrap();
+
+        assert!(dawg.search("apple"));
+        assert!(dawg.search("wizard"));
+        assert!(dawg.search("zoo"));
+        assert!(!dawg.search("app"));
+
+        let result = WordListTrieBuilder::new(&["zug", "straße"]).build(&mut Dawg::new());
+        assert!(matches!(result, Err(Error::InvalidCharacter { character: 'ß', line: 2 })));
+    }
+
+    #[test]
+    fn txt_trie_builder_uses_trie_alphabet() {
+        let path = std::env::temp_dir().join(format!("german-{}.txt", std::process::id()));
+        std::fs::write(&path, "Straße\nÄpfel\nzug\n\nApfel\n").unwrap();
+        let mut simple_trie = SimpleTrie::with_alphabet(Alphabet::german());
+        TxtFileTrieBuilder::new(path.to_str().unwrap()).unwrap().build(&mut simple_trie).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(simple_trie.search("straße"));
+        assert!(simple_trie.search("äpfel"));
+        assert!(simple_trie.search("apfel"));
+        assert!(!simple_trie.search("strasse"));
+    }
+
+    #[test]
+    fn txt_trie_builder_validates_characters() {
+        let path = std::env::temp_dir().join(format!("invalid-{}.txt", std::process::id()));
+        std::fs::write(&path, "zug\nstraße\n").unwrap();
+        let txt_file_trie_builder = TxtFileTrieBuilder::new(path.to_str().unwrap()).unwrap();
+        let mut simple_trie = SimpleTrie::new();
+        let result = txt_file_trie_builder.build(&mut simple_trie);
+        std::fs::remove_file(&path).unwrap();
+
+        let error = result.unwrap_err();
+        assert!(matches!(error, Error::InvalidCharacter { character: 'ß', line: 2 }));
+        assert_eq!(error.to_string(), "Invalid character ß at line 2");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,73 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// Errors returned when loading dictionaries or reading puzzle input.
+#[derive(Debug)]
+pub enum Error {
+    FileNotFound(String),
+    /// The file is neither a `.txt` word list nor a compiled dictionary.
+    UnsupportedFormat(String),
+    /// A word contains a letter outside the dictionary's alphabet. Lines are
+    /// counted from 1, and for in-memory word lists are the word's position.
+    InvalidCharacter { character: char, line: usize },
+    NotInAlphabet(char),
+    /// A word was inserted into a DAWG after a word that sorts after it.
+    UnsortedWords { word: String, previous: String },
+    /// A matrix row, counted from 0, is not as wide as the first row.
+    RaggedGrid { row: usize, expected: usize, found: usize },
+    /// A board square, counted from 0, holds neither a tile nor an empty mark.
+    InvalidSquare { row: usize, column: usize, character: char },
+    EmptyGrid,
+    /// A board does not have the number of rows its game is played on.
+    BoardSize { expected: usize, found: usize },
+    CorruptDictionary(&'static str),
+    InvalidPattern(String),
+    InvalidFeedback(String),
+    /// A puzzle's letters or layout break the rules of its game.
+    InvalidPuzzle(String),
+    Io(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::FileNotFound(path) => write!(f, "File path does not exist: {}", path),
+            Error::UnsupportedFormat(path) => write!(f, "Unsupported dictionary format: {}", path),
+            Error::InvalidCharacter { character, line } => {
+                write!(f, "Invalid character {} at line {}", character, line)
+            }
+            Error::NotInAlphabet(character) => write!(f, "Character {} is not in the alphabet", character),
+            Error::UnsortedWords { word, previous } => {
+                write!(f, "Words must be inserted in lexicographic order: {} after {}", word, previous)
+            }
+            Error::RaggedGrid { row, expected, found } => {
+                write!(f, "Row {} has {} columns, expected {}", row, found, expected)
+            }
+            Error::InvalidSquare { row, column, character } => {
+                write!(f, "Invalid square {} at row {}, column {}", character, row, column)
+            }
+            Error::EmptyGrid => write!(f, "Matrix has no cells"),
+            Error::BoardSize { expected, found } => write!(f, "Board has {} rows, expected {}", found, expected),
+            Error::CorruptDictionary(reason) => write!(f, "Corrupt compiled dictionary: {}", reason),
+            Error::InvalidPattern(reason) => write!(f, "Invalid pattern: {}", reason),
+            Error::InvalidFeedback(reason) => write!(f, "Invalid feedback: {}", reason),
+            Error::InvalidPuzzle(reason) => write!(f, "Invalid puzzle: {}", reason),
+            Error::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(error)
+    }
+}
@@ -1,46 +1,343 @@
 
 use std::collections::HashSet;
 use std::rc::Rc;
+use crate::dictionaries::{Dictionary, LoadedDictionary};
+use crate::dictionaries::compiled::compile;
 use crate::dictionaries::tries::Trie;
 use crate::word_finder::anagram_word_finder::{AnagramWordFinder};
 use crate::word_finder::matrix::Matrix;
+use crate::word_finder::phrase_anagram_word_finder::PhraseAnagramWordFinder;
+use crate::word_finder::boggle::BoggleWordFinder;
+use crate::word_finder::boggle_generator::BoggleBoardGenerator;
 use crate::word_finder::matrix_word_finder::MatrixWordFinder;
-use crate::dictionaries::tries::trie::{SimpleTrie};
-use crate::dictionaries::tries::trie_builder::{TrieBuilder, TxtFileTrieBuilder};
-use crate::word_finder::{BasicWordFilters, WordFinder};
+use crate::dictionaries::tries::dawg::Dawg;
+use crate::dictionaries::tries::trie_builder::{TrieBuilder, TxtFileTrieBuilder, WordListTrieBuilder};
+use crate::word_finder::word_search::WordSearchWordFinder;
+use crate::word_finder::pattern_word_finder::PatternWordFinder;
+use crate::word_finder::regex::RegexWordFinder;
+use crate::word_finder::scrabble::ScrabbleMoveGenerator;
+use crate::word_finder::wordle::WordleSolver;
+use crate::word_finder::spelling_bee::SpellingBeeWordFinder;
+use crate::word_finder::letter_boxed::LetterBoxedSolver;
+use crate::word_finder::word_ladder::WordLadderFinder;
+use crate::word_finder::cryptogram::CryptogramSolver;
+use crate::word_finder::crossword::CrosswordFiller;
+use crate::word_finder::hangman::HangmanSolver;
+use crate::word_finder::WordFinder;
 
 mod word_finder;
 mod dictionaries;
+mod error;
+
+pub use crate::error::Error;
+pub use crate::word_finder::{filters, BasicWordFilters, WordFilters};
+pub use crate::dictionaries::alphabet::Alphabet;
+pub use crate::word_finder::anagram_word_finder::{AnagramMatch, BLANK};
+pub use crate::word_finder::phrase_anagram_word_finder::PhraseAnagramOptions;
+pub use crate::word_finder::matrix::{Direction, Point};
+pub use crate::word_finder::matrix_word_finder::WordPaths;
+pub use crate::word_finder::boggle::{BoggleResult, BoggleRules};
+pub use crate::word_finder::boggle_generator::{BoggleConstraints, BoggleLetters, GeneratedBoggleBoard};
+pub use crate::word_finder::word_search::WordSearchMatch;
+pub use crate::word_finder::regex::Regex;
+pub use crate::word_finder::scrabble::{PlacedTile, Premium, ScrabbleBoard, ScrabbleMove, BINGO_BONUS, BOARD_SIZE, RACK_SIZE};
+pub use crate::word_finder::scoring::{LetterValues, ScoredWord};
+pub use crate::word_finder::wordle::{Guess, LetterFeedback, WordleFeedback, WordleResult};
+pub use crate::word_finder::spelling_bee::{spelling_bee_score, SpellingBeeResult, PANGRAM_BONUS, SPELLING_BEE_MIN_LENGTH};
+pub use crate::word_finder::letter_boxed::LETTER_BOXED_MIN_LENGTH;
+pub use crate::word_finder::word_ladder::WordLadderOptions;
+pub use crate::word_finder::cryptogram::{pattern_signature, Cryptogram, CryptogramSolution};
+pub use crate::word_finder::crossword::{CrosswordEntry, CrosswordFill};
+pub use crate::word_finder::hangman::{HangmanResult, LetterSuggestion};
 
 pub const SCRABBLE_DICTIONARY_PATH: &str = "scrabble-dictionary.txt";
 
+/// Compiles the `.txt` word list at `txt_path` into a binary dictionary at
+/// `output_path`, which `WordService::new` can then load without parsing.
+/// Compiled dictionaries use the `.dawg` extension.
+pub fn compile_dictionary(txt_path: &str, output_path: &str) -> Result<(), Error> {
+    let mut dawg = Dawg::new();
+    TxtFileTrieBuilder::new(txt_path)?.build(&mut dawg)?;
+    compile(&dawg, output_path)
+}
+
 pub struct WordService {
-    matrix_word_finder: MatrixWordFinder<SimpleTrie>,
-    anagram_word_finder: AnagramWordFinder<SimpleTrie>,
+    dictionary: Rc<LoadedDictionary>,
+    matrix_word_finder: MatrixWordFinder<LoadedDictionary>,
+    anagram_word_finder: AnagramWordFinder<LoadedDictionary>,
+    phrase_anagram_word_finder: PhraseAnagramWordFinder<LoadedDictionary>,
+    boggle_word_finder: BoggleWordFinder<LoadedDictionary>,
+    boggle_board_generator: BoggleBoardGenerator<LoadedDictionary>,
+    word_search_word_finder: WordSearchWordFinder<LoadedDictionary>,
+    pattern_word_finder: PatternWordFinder<LoadedDictionary>,
+    regex_word_finder: RegexWordFinder<LoadedDictionary>,
+    scrabble_move_generator: ScrabbleMoveGenerator<LoadedDictionary>,
+    wordle_solver: WordleSolver<LoadedDictionary>,
+    spelling_bee_word_finder: SpellingBeeWordFinder<LoadedDictionary>,
+    letter_boxed_solver: LetterBoxedSolver<LoadedDictionary>,
+    word_ladder_finder: WordLadderFinder<LoadedDictionary>,
+    cryptogram_solver: CryptogramSolver<LoadedDictionary>,
+    crossword_filler: CrosswordFiller<LoadedDictionary>,
+    hangman_solver: HangmanSolver<LoadedDictionary>,
 }
 
 impl WordService {
-    pub fn new(path: &str) -> WordService {
-        let mut simple_trie = Rc::new(SimpleTrie::new());
-        let txt_file_trie_builder = TxtFileTrieBuilder::new(path);
-        txt_file_trie_builder.build(Rc::make_mut(&mut simple_trie));
-
-        WordService {
-            matrix_word_finder: MatrixWordFinder::new(Rc::clone(&simple_trie), true),
-            anagram_word_finder: AnagramWordFinder::new(Rc::clone(&simple_trie)),
+    /// Loads a dictionary from either a `.txt` word list or a compiled `.dawg` file.
+    pub fn new(path: &str) -> Result<WordService, Error> {
+        WordService::with_alphabet(path, Alphabet::english())
+    }
+
+    /// Loads a word list made of the letters of `alphabet`. Compiled files
+    /// always use the alphabet they were compiled with.
+    pub fn with_alphabet(path: &str, alphabet: Alphabet) -> Result<WordService, Error> {
+        let dictionary = Rc::new(LoadedDictionary::load(path, alphabet)?);
+
+        Ok(WordService {
+            matrix_word_finder: MatrixWordFinder::new(Rc::clone(&dictionary), true),
+            anagram_word_finder: AnagramWordFinder::new(Rc::clone(&dictionary)),
+            phrase_anagram_word_finder: PhraseAnagramWordFinder::new(Rc::clone(&dictionary)),
+            boggle_word_finder: BoggleWordFinder::new(Rc::clone(&dictionary)),
+            boggle_board_generator: BoggleBoardGenerator::new(Rc::clone(&dictionary)),
+            word_search_word_finder: WordSearchWordFinder::new(Rc::clone(&dictionary)),
+            pattern_word_finder: PatternWordFinder::new(Rc::clone(&dictionary)),
+            regex_word_finder: RegexWordFinder::new(Rc::clone(&dictionary)),
+            scrabble_move_generator: ScrabbleMoveGenerator::new(Rc::clone(&dictionary)),
+            wordle_solver: WordleSolver::new(Rc::clone(&dictionary)),
+            spelling_bee_word_finder: SpellingBeeWordFinder::new(Rc::clone(&dictionary)),
+            letter_boxed_solver: LetterBoxedSolver::new(Rc::clone(&dictionary)),
+            word_ladder_finder: WordLadderFinder::new(Rc::clone(&dictionary)),
+            cryptogram_solver: CryptogramSolver::new(Rc::clone(&dictionary)),
+            crossword_filler: CrosswordFiller::new(Rc::clone(&dictionary)),
+            hangman_solver: HangmanSolver::new(Rc::clone(&dictionary)),
+            dictionary,
+        })
+    }
+
+    /// Empty [`BasicWordFilters`] whose letters are normalized with the
+    /// dictionary's alphabet, so they match words as the dictionary stores them.
+    pub fn filters(&self) -> BasicWordFilters {
+        BasicWordFilters::for_alphabet(self.dictionary.alphabet())
+    }
+
+    /// Finds the words made of some or all of the letters of `word` that pass
+    /// `filters`. Characters outside the alphabet, [`BLANK`] included, are
+    /// ignored; use [`WordService::find_anagrams_with_blanks`] for blank tiles.
+    pub fn find_anagrams<F: WordFilters>(&self, word: &str, filters: &F) -> HashSet<String> {
+        self.anagram_word_finder.search(&String::from(word), filters)
+    }
+
+    /// Finds the same words as [`WordService::find_anagrams`], best first:
+    /// by score under `letter_values`, then by length, then alphabetically.
+    /// Any other search can be ranked the same way with [`LetterValues::rank`].
+    pub fn find_anagrams_ranked<F: WordFilters>(
+        &self,
+        word: &str,
+        filters: &F,
+        letter_values: &LetterValues,
+    ) -> Vec<ScoredWord> {
+        letter_values.rank(self.find_anagrams(word, filters))
+    }
+
+    /// Finds the words in a rack where each `?` is a blank tile, reporting
+    /// which letters the blanks were played as. Each word is reported once,
+    /// with the blanks standing for the letters the rack has run out of.
+    /// Returns [`Error::NotInAlphabet`] for any other letter the dictionary
+    /// can't spell.
+    pub fn find_anagrams_with_blanks<F: WordFilters>(
+        &self,
+        rack: &str,
+        filters: &F,
+    ) -> Result<HashSet<AnagramMatch>, Error> {
+        self.anagram_word_finder.search_with_blanks(rack, filters)
+    }
+
+    /// Finds anagrams of `phrase` made of one or more words that together use
+    /// every letter exactly once, such as "dirty room" for "dormitory".
+    pub fn find_phrase_anagrams(&self, phrase: &str, options: &PhraseAnagramOptions) -> Vec<String> {
+        self.phrase_anagram_word_finder.search(phrase, options)
+    }
+
+    /// Finds the words matching a crossword-style pattern, in alphabetical
+    /// order. `?` or `.` is any letter, `*` any number of letters, `[aeiou]`
+    /// one of a set of letters, and `{m,n}` repeats what comes before it, so
+    /// `b.{2,4}d` matches "bird" and "bread".
+    pub fn find_pattern(&self, pattern: &str) -> Result<Vec<String>, Error> {
+        self.pattern_word_finder.search(pattern)
+    }
+
+    /// Parses a [`Regex`] whose letters are normalized with the dictionary's
+    /// alphabet, to be passed as a filter to the other searches.
+    pub fn regex(&self, pattern: &str) -> Result<Regex, Error> {
+        Regex::with_alphabet(pattern, self.dictionary.alphabet())
+    }
+
+    /// Finds the words matching a regular expression, in alphabetical order.
+    /// See [`Regex`] for the supported syntax; a [`Regex`] can also be passed
+    /// as a filter to the other searches.
+    pub fn find_regex(&self, pattern: &str) -> Result<Vec<String>, Error> {
+        self.regex_word_finder.search(pattern)
+    }
+
+    /// Finds the words passing `filters` that can be spelled by moving between
+    /// adjacent cells of the matrix, diagonals included.
+    pub fn find_words_in_matrix<F: WordFilters>(
+        &self,
+        nested_slice: &[&[char]],
+        filters: &F,
+    ) -> Result<HashSet<String>, Error> {
+        let matrix = Matrix::new(nested_slice)?;
+        Ok(self.matrix_word_finder.search(&matrix, filters))
+    }
+
+    /// Finds the same words as [`WordService::find_words_in_matrix`], ranked
+    /// like [`WordService::find_anagrams_ranked`].
+    pub fn find_words_in_matrix_ranked<F: WordFilters>(
+        &self,
+        nested_slice: &[&[char]],
+        filters: &F,
+        letter_values: &LetterValues,
+    ) -> Result<Vec<ScoredWord>, Error> {
+        Ok(letter_values.rank(self.find_words_in_matrix(nested_slice, filters)?))
+    }
+
+    /// Finds the words in a matrix along with the cells spelling them, so
+    /// they can be highlighted. Returns every path to each word if
+    /// `all_paths` is set, and only the first one found otherwise.
+    pub fn find_word_paths_in_matrix<F: WordFilters>(
+        &self,
+        nested_slice: &[&[char]],
+        filters: &F,
+        all_paths: bool,
+    ) -> Result<WordPaths, Error> {
+        let matrix = Matrix::new(nested_slice)?;
+        Ok(self.matrix_word_finder.search_paths(&matrix, filters, all_paths))
+    }
+
+    /// Solves a Boggle board whose dice may show several letters, like "Qu",
+    /// returning the points of each word and the total board score.
+    pub fn find_boggle_words(&self, board: &[&[&str]], rules: &BoggleRules) -> Result<BoggleResult, Error> {
+        let matrix = Matrix::from_tiles(board)?;
+        Ok(self.boggle_word_finder.search(&matrix, rules))
+    }
+
+    /// Finds the same words as [`WordService::find_boggle_words`], ranked by
+    /// `letter_values` rather than by Boggle points, like
+    /// [`WordService::find_anagrams_ranked`].
+    pub fn find_boggle_words_ranked(
+        &self,
+        board: &[&[&str]],
+        rules: &BoggleRules,
+        letter_values: &LetterValues,
+    ) -> Result<Vec<ScoredWord>, Error> {
+        Ok(letter_values.rank(self.find_boggle_words(board, rules)?.words.into_keys()))
+    }
+
+    /// Generates random `size` x `size` Boggle boards from `letters` until one
+    /// meets `constraints` under `rules`, returning it with its words. The
+    /// same seed always gives the same board, e.g. a date for a daily puzzle.
+    pub fn generate_boggle_board(
+        &self,
+        seed: u64,
+        size: usize,
+        letters: &BoggleLetters,
+        rules: &BoggleRules,
+        constraints: &BoggleConstraints,
+    ) -> Result<Option<GeneratedBoggleBoard>, Error> {
+        self.boggle_board_generator.generate(seed, size, letters, rules, constraints)
+    }
+
+    /// Solves a word search puzzle, where words only read in a straight line.
+    /// Looks for the puzzle's own `words` if given, and for every dictionary
+    /// word of at least `min_length` letters otherwise. Spaces and hyphens in
+    /// `words` are ignored, so "ICE CREAM" is found as "icecream".
+    pub fn solve_word_search(
+        &self,
+        nested_slice: &[&[char]],
+        words: Option<&[&str]>,
+        min_length: usize,
+    ) -> Result<Vec<WordSearchMatch>, Error> {
+        let matrix = Matrix::new(nested_slice)?;
+        match words {
+            Some(words) => {
+                let mut dawg = Dawg::with_alphabet(self.dictionary.alphabet().clone());
+                WordListTrieBuilder::new(words).build(&mut dawg)?;
+                Ok(WordSearchWordFinder::new(Rc::new(dawg)).search(&matrix, min_length))
+            }
+            None => Ok(self.word_search_word_finder.search(&matrix, min_length)),
         }
     }
 
-    pub fn find_anagrams(&self, word: &str) -> HashSet<String> {
-        // TODO: implement filters for WordService
-        let filters: BasicWordFilters = Default::default();
-        self.anagram_word_finder.search(&String::from(word), &filters)
+    /// Finds every legal Scrabble move for `rack` on `board`, from highest to
+    /// lowest score. Blanks in the rack are written as [`BLANK`]. Returns
+    /// [`Error::NotInAlphabet`] for any other letter the dictionary can't
+    /// spell, and [`Error::InvalidPuzzle`] for more than [`RACK_SIZE`] tiles.
+    pub fn find_scrabble_moves(
+        &self,
+        board: &ScrabbleBoard,
+        rack: &str,
+        letter_values: &LetterValues,
+    ) -> Result<Vec<ScrabbleMove>, Error> {
+        self.scrabble_move_generator.generate(board, rack, letter_values)
+    }
+
+    /// Finds the words of `length` letters that would have given the same
+    /// feedback to every guess so far, and suggests the next guess expected
+    /// to narrow them down the most.
+    pub fn solve_wordle(&self, length: usize, guesses: &[Guess]) -> WordleResult {
+        self.wordle_solver.solve(length, guesses)
+    }
+
+    /// Solves a Spelling Bee: words of four or more letters using `center`
+    /// and any of the `outer` letters, each as many times as needed.
+    pub fn solve_spelling_bee(&self, center: char, outer: &str) -> Result<SpellingBeeResult, Error> {
+        self.spelling_bee_word_finder.search(center, outer)
+    }
+
+    /// Solves a Letter Boxed puzzle given the letters of each side, returning
+    /// every chain of the fewest words using all the letters, or nothing if
+    /// it takes more than `max_words`. Chains are ordered by total length,
+    /// then alphabetically.
+    pub fn solve_letter_boxed(&self, sides: &[&str], max_words: usize) -> Result<Vec<Vec<String>>, Error> {
+        self.letter_boxed_solver.solve(sides, max_words)
+    }
+
+    /// Finds the shortest word ladders from `start` to `target`, where each
+    /// step changes one letter and every rung is a dictionary word. Both
+    /// words must be in the dictionary, and of the same length unless
+    /// `options` allows adding or removing letters.
+    pub fn find_word_ladders(
+        &self,
+        start: &str,
+        target: &str,
+        options: &WordLadderOptions,
+    ) -> Result<Vec<Vec<String>>, Error> {
+        self.word_ladder_finder.search(start, target, options)
+    }
+
+    /// Solves a substitution cipher or codeword puzzle, returning up to
+    /// `max_solutions` keys under which every word is a dictionary word.
+    pub fn solve_cryptogram(
+        &self,
+        cryptogram: &Cryptogram,
+        max_solutions: usize,
+    ) -> Result<Vec<CryptogramSolution>, Error> {
+        self.cryptogram_solver.solve(cryptogram, max_solutions)
     }
 
-    pub fn find_words_in_matrix(&self, nested_slice: &[&[char]]) -> HashSet<String> {
-        let matrix = Matrix::new(nested_slice);
-        let filters: BasicWordFilters = Default::default();
-        self.matrix_word_finder.search(&matrix, &filters)
+    /// Fills a crossword grid, one string per row with `#` for black squares,
+    /// `.` for empty cells and letters for cells filled in already. Every
+    /// across and down slot gets a different dictionary word; `None` if there
+    /// is no such fill.
+    pub fn fill_crossword(&self, grid: &[&str]) -> Result<Option<CrosswordFill>, Error> {
+        let matrix = Matrix::with_blocks(grid)?;
+        self.crossword_filler.fill(&matrix)
+    }
+
+    /// Finds the words matching a Hangman mask such as "_a__e_" that contain
+    /// none of the `wrong_guesses`, and ranks the letters left to guess by
+    /// how many of those words contain them.
+    pub fn solve_hangman(&self, mask: &str, wrong_guesses: &str) -> Result<HangmanResult, Error> {
+        self.hangman_solver.solve(mask, wrong_guesses)
     }
 }
 
@@ -50,34 +347,455 @@ mod test {
 
     #[test]
     fn test_new_word_service() {
-        WordService::new(SCRABBLE_DICTIONARY_PATH);
+        WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+    }
+
+    #[test]
+    fn test_word_service_errors() {
+        assert!(matches!(WordService::new("inexistent.txt"), Err(Error::FileNotFound(_))));
+        assert!(matches!(WordService::new("Cargo.toml"), Err(Error::UnsupportedFormat(_))));
+
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let result = word_service.find_words_in_matrix(&[&['a', 'b'], &['c']], &BasicWordFilters::default());
+        assert!(matches!(result, Err(Error::RaggedGrid { row: 1, expected: 2, found: 1 })));
+
+        let result = word_service.solve_word_search(&[&['a']], Some(&["cat", "café"]), 1);
+        assert!(matches!(result, Err(Error::InvalidCharacter { character: 'é', line: 2 })));
+    }
+
+    #[test]
+    fn test_new_word_service_from_compiled_dictionary() {
+        let path = std::env::temp_dir().join(format!("scrabble-{}.dawg", std::process::id()));
+        let path = path.to_str().unwrap();
+        compile_dictionary(SCRABBLE_DICTIONARY_PATH, path).unwrap();
+
+        let word_service = WordService::new(path).unwrap();
+        let words = word_service.find_anagrams("people", &BasicWordFilters::default());
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(words.len(), 24);
+        assert!(words.contains("pope"));
     }
 
     #[test]
     fn test_find_anagrams() {
-        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH);
-        let words = word_service.find_anagrams("people");
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let words = word_service.find_anagrams("people", &BasicWordFilters::default());
 
         assert_eq!(words.len(), 24);
         assert!(words.contains("pope"));
         assert!(words.contains("peep"));
     }
 
+    #[test]
+    fn test_find_anagrams_with_filters() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let filters = BasicWordFilters::default().starts_with("re").contains("at");
+        let words = word_service.find_anagrams("treacle", &filters);
+
+        assert!(!words.contains("react"));
+        assert!(words.contains("relate"));
+        assert!(words.iter().all(|word| word.starts_with("re") && word.contains("at")));
+
+        let filters = BasicWordFilters::default().min_length(4).max_length(5).excluding("o");
+        let words = word_service.find_anagrams("people", &filters);
+        assert!(words.contains("peep"));
+        assert!(!words.contains("pope"));
+        assert!(!words.contains("people"));
+
+        let filters = word_service.filters().starts_with("RE").excluding("O");
+        let words = word_service.find_anagrams("treacle", &filters);
+        assert!(words.contains("relate"));
+        assert!(words.iter().all(|word| word.starts_with("re")));
+        // The English alphabet does not fold accents, so no word starts with "ré"
+        let filters = word_service.filters().starts_with("Ré");
+        assert!(word_service.find_anagrams("treacle", &filters).is_empty());
+    }
+
+    #[test]
+    fn test_find_anagrams_with_blanks() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let matches = word_service.find_anagrams_with_blanks("retin??", &BasicWordFilters::default()).unwrap();
+
+        let retinal = AnagramMatch { word: "retinal".to_string(), blank_positions: vec![5, 6] };
+        let latrine = AnagramMatch { word: "latrine".to_string(), blank_positions: vec![0, 1] };
+        assert!(matches.contains(&retinal));
+        assert!(matches.contains(&latrine));
+        assert_eq!(latrine.blank_letters(), vec!['l', 'a']);
+        assert!(matches.iter().all(|m| m.word.len() <= 7));
+        assert_eq!(matches.iter().filter(|m| m.word == "retinal").count(), 1);
+        let words = word_service.find_anagrams("retin??", &BasicWordFilters::default());
+        assert!(!words.contains("retinas"));
+        assert!(words.contains("inert"));
+
+        let filtered = word_service.find_anagrams_with_blanks("retin??", &word_service.filters().starts_with("lat")).unwrap();
+        assert!(filtered.contains(&latrine));
+        assert!(!filtered.contains(&retinal));
+
+        let result = word_service.find_anagrams_with_blanks("ret1n??", &BasicWordFilters::default());
+        assert!(matches!(result, Err(Error::NotInAlphabet('1'))));
+    }
+
+    #[test]
+    fn test_find_phrase_anagrams() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let options = PhraseAnagramOptions::new(2, 3, 500);
+        let phrases = word_service.find_phrase_anagrams("dormitory", &options);
+
+        assert!(phrases.contains(&"dormitory".to_string()));
+        assert!(phrases.contains(&"dirty room".to_string()));
+        assert!(phrases.iter().all(|phrase| phrase.split(' ').count() <= 2));
+        assert!(phrases.iter().all(|phrase| phrase.split(' ').all(|word| word.len() >= 3)));
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+
+        let words = word_service.find_pattern("c?t").unwrap();
+        assert_eq!(words, vec!["cat", "cot", "cut"]);
+
+        let words = word_service.find_pattern("*ation").unwrap();
+        assert!(words.contains(&"station".to_string()));
+        assert!(words.iter().all(|word| word.ends_with("ation")));
+
+        let words = word_service.find_pattern("*e*e*e*e").unwrap();
+        assert!(words.contains(&"belvedere".to_string()));
+        assert!(words.iter().all(|word| word.ends_with('e') && word.matches('e').count() >= 4));
+        assert_eq!(word_service.find_pattern("*****e").unwrap(), word_service.find_pattern("*e").unwrap());
+
+        let words = word_service.find_pattern("[aeiou]??s").unwrap();
+        assert!(words.contains(&"eyes".to_string()));
+        assert!(words.iter().all(|word| word.len() == 4 && word.ends_with('s')));
+
+        let words = word_service.find_pattern("b.{2,4}d").unwrap();
+        assert!(words.contains(&"bird".to_string()));
+        assert!(words.contains(&"bread".to_string()));
+        assert!(!words.contains(&"bad".to_string()));
+
+        assert!(matches!(word_service.find_pattern("c[at"), Err(Error::InvalidPattern(_))));
+    }
+
+    #[test]
+    fn test_find_regex() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+
+        let words = word_service.find_regex("^(un|re)zip").unwrap();
+        assert_eq!(words, vec!["unzip", "unzipped", "unzipping", "unzips"]);
+
+        let words = word_service.find_anagrams("reacting", &Regex::new("^c.+(ing|ed)$").unwrap());
+        let regex = word_service.regex("^C.+(ING|ED)$").unwrap();
+        assert_eq!(word_service.find_anagrams("reacting", &regex), words);
+        assert!(words.contains("caring"));
+        assert!(words.iter().all(|word| word.starts_with('c')));
+        assert!(matches!(word_service.find_regex("(ab"), Err(Error::InvalidPattern(_))));
+    }
+
     #[test]
     fn test_find_words_in_matrix() {
-        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH);
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
         let nested_slice: &[&[char]] = &[
             &['r', 'u', 'g', 's'],
             &['e', 'a', 't', 's'],
             &['s', 'p', 'o', 't'],
             &['h', 'e', 'l', 'p'],
         ];
-        let words = word_service.find_words_in_matrix(&nested_slice);
+        let words = word_service.find_words_in_matrix(nested_slice, &BasicWordFilters::default()).unwrap();
 
         assert_eq!(words.len(), 320);
         assert!(words.contains("stoats"));
         assert!(words.contains("guttles"));
         assert!(words.contains("helots"));
         assert!(words.contains("plots"));
+
+        let filters = BasicWordFilters::default().ends_with("ts").min_length(5);
+        let words = word_service.find_words_in_matrix(nested_slice, &filters).unwrap();
+        assert!(words.contains("stoats"));
+        assert!(!words.contains("eats"));
+        assert!(words.iter().all(|word| word.ends_with("ts") && word.len() >= 5));
+    }
+
+    #[test]
+    fn test_find_word_paths_in_matrix() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let nested_slice: &[&[char]] = &[
+            &['r', 'u', 'g', 's'],
+            &['e', 'a', 't', 's'],
+            &['s', 'p', 'o', 't'],
+            &['h', 'e', 'l', 'p'],
+        ];
+        let paths = word_service.find_word_paths_in_matrix(nested_slice, &BasicWordFilters::default(), false).unwrap();
+
+        assert_eq!(paths.len(), 320);
+        assert_eq!(paths["help"], vec![vec![(3, 0), (3, 1), (3, 2), (3, 3)]]);
+        assert!(paths.values().all(|paths| paths.len() == 1));
+
+        let all_paths = word_service.find_word_paths_in_matrix(nested_slice, &BasicWordFilters::default(), true).unwrap();
+        assert_eq!(all_paths["spot"], vec![
+            vec![(2, 0), (2, 1), (2, 2), (2, 3)],
+            vec![(2, 0), (2, 1), (2, 2), (1, 2)],
+        ]);
+        for (word, paths) in all_paths {
+            for path in paths {
+                let spelled: String = path.iter().map(|&(row, col)| nested_slice[row][col]).collect();
+                assert_eq!(spelled, word);
+            }
+        }
+
+        let filters = word_service.filters().starts_with("sp");
+        let filtered = word_service.find_word_paths_in_matrix(nested_slice, &filters, false).unwrap();
+        assert!(filtered.contains_key("spot"));
+        assert!(filtered.keys().all(|word| word.starts_with("sp")));
+    }
+
+    #[test]
+    fn test_find_boggle_words() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let board: &[&[&str]] = &[
+            &["Qu", "i", "t", "e"],
+            &["a", "r", "s", "n"],
+            &["t", "e", "l", "o"],
+            &["d", "g", "h", "p"],
+        ];
+        let result = word_service.find_boggle_words(board, &BoggleRules::classic()).unwrap();
+
+        assert_eq!(result.words["quite"], 2);
+        assert_eq!(result.words["quits"], 2);
+        assert_eq!(result.words["rest"], 1);
+        assert!(!result.words.contains_key("qi"));
+        assert!(result.words.keys().all(|word| word.len() >= 3));
+        assert_eq!(result.total_score, result.words.values().sum::<u32>());
+    }
+
+    #[test]
+    fn test_generate_boggle_board() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let letters = BoggleLetters::classic_dice();
+        let rules = BoggleRules::classic();
+        let constraints = BoggleConstraints::default().min_words(150).long_word(7);
+
+        let generated = word_service.generate_boggle_board(20261018, 4, &letters, &rules, &constraints).unwrap().unwrap();
+
+        assert!(generated.result.words.len() >= 150);
+        assert!(generated.result.words.keys().any(|word| word.len() >= 7));
+        let rows: Vec<Vec<&str>> = generated.board.iter().map(|row| row.iter().map(String::as_str).collect()).collect();
+        let rows: Vec<&[&str]> = rows.iter().map(Vec::as_slice).collect();
+        let solved = word_service.find_boggle_words(&rows, &rules).unwrap();
+        assert_eq!(solved.words, generated.result.words);
+
+        let again = word_service.generate_boggle_board(20261018, 4, &letters, &rules, &constraints).unwrap().unwrap();
+        assert_eq!(again.board, generated.board);
+
+        let constraints = BoggleConstraints::default().score_range(50, 100);
+        let generated = word_service
+            .generate_boggle_board(1, 5, &BoggleLetters::english_frequencies(), &BoggleRules::big_boggle(), &constraints)
+            .unwrap()
+            .unwrap();
+        assert!((50..=100).contains(&generated.result.total_score));
+    }
+
+    #[test]
+    fn test_solve_word_search() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let grid: &[&[char]] = &[
+            &['r', 'u', 'g', 's'],
+            &['e', 'a', 't', 's'],
+            &['s', 'p', 'o', 't'],
+            &['h', 'e', 'l', 'p'],
+        ];
+
+        let matches = word_service.solve_word_search(grid, Some(&["SPOT", "help", "tops", "rush"]), 1).unwrap();
+        let words: HashSet<&str> = matches.iter().map(|found| found.word.as_str()).collect();
+        assert_eq!(words, HashSet::from(["spot", "help", "tops"]));
+        assert!(matches.contains(&WordSearchMatch {
+            word: "tops".to_string(),
+            start: (2, 3),
+            direction: Direction::Left,
+            length: 4,
+        }));
+
+        let matches = word_service.solve_word_search(grid, None, 4).unwrap();
+        assert!(matches.iter().any(|found| found.word == "eats" && found.start == (1, 0)));
+        assert!(matches.iter().all(|found| found.length >= 4));
+
+        let matches = word_service.solve_word_search(grid, Some(&["RUGS", "EA-TS", "SPOT HELP", "a"]), 1).unwrap();
+        let found: Vec<(&str, (usize, usize))> = matches.iter().map(|found| (found.word.as_str(), found.start)).collect();
+        assert_eq!(found, vec![("rugs", (0, 0)), ("eats", (1, 0)), ("a", (1, 1))]);
+    }
+
+    #[test]
+    fn test_find_anagrams_ranked() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let filters = BasicWordFilters::default().min_length(3);
+        let ranked = word_service.find_anagrams_ranked("zebra", &filters, &LetterValues::scrabble_english());
+
+        // Ties on score and length are broken alphabetically
+        assert_eq!(ranked[0], ScoredWord { word: "braze".to_string(), score: 16 });
+        assert_eq!(ranked[1], ScoredWord { word: "zebra".to_string(), score: 16 });
+        assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert_eq!(ranked.len(), word_service.find_anagrams("zebra", &filters).len());
+
+        let ranked = word_service.find_anagrams_ranked("zebra", &filters, &LetterValues::words_with_friends());
+        assert_eq!(ranked[0].score, 17);
+
+        let letter_values = LetterValues::scrabble_english();
+        let grid: &[&[char]] = &[&['z', 'e', 'b'], &['x', 'r', 'a'], &['q', 'u', 'y']];
+        let ranked = word_service.find_words_in_matrix_ranked(grid, &filters, &letter_values).unwrap();
+        assert_eq!(ranked.len(), word_service.find_words_in_matrix(grid, &filters).unwrap().len());
+        assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert!(ranked.iter().all(|scored| scored.score == letter_values.score(&scored.word)));
+
+        let board: &[&[&str]] = &[&["z", "e", "b"], &["x", "r", "a"], &["Qu", "u", "y"]];
+        let ranked = word_service.find_boggle_words_ranked(board, &BoggleRules::classic(), &letter_values).unwrap();
+        let result = word_service.find_boggle_words(board, &BoggleRules::classic()).unwrap();
+        assert_eq!(ranked.len(), result.words.len());
+        assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    }
+
+    #[test]
+    fn test_solve_wordle() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let guesses = [
+            Guess::new("crane", "....g").unwrap(),
+            Guess::new("house", ".y.yg").unwrap(),
+            Guess::new("spoke", "g.ggg").unwrap(),
+        ];
+
+        let result = word_service.solve_wordle(5, &guesses);
+        assert_eq!(result.candidates, vec!["smoke", "stoke"]);
+        assert_eq!(result.suggestion, Some("smoke".to_string()));
+
+        let result = word_service.solve_wordle(5, &[]);
+        assert!(result.candidates.len() > 8000);
+        assert!(result.suggestion.is_some());
+    }
+
+    #[test]
+    fn test_solve_spelling_bee() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let result = word_service.solve_spelling_bee('g', "ailmno").unwrap();
+
+        assert!(result.pangrams.contains(&"gloaming".to_string()));
+        assert_eq!(result.words["gloaming"], 8 + PANGRAM_BONUS);
+        assert_eq!(result.words["gong"], 1);
+        assert!(!result.words.contains_key("loam"));
+        assert!(result.words.keys().all(|word| word.contains('g') && word.len() >= SPELLING_BEE_MIN_LENGTH));
+        assert_eq!(result.total_score, result.words.values().sum::<u32>());
+    }
+
+    #[test]
+    fn test_solve_letter_boxed() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let sides = ["tup", "rao", "cmi", "lyn"];
+
+        let chains = word_service.solve_letter_boxed(&sides, 3).unwrap();
+
+        assert!(!chains.is_empty());
+        let words = chains[0].len();
+        assert!(chains.iter().all(|chain| chain.len() == words));
+        for chain in &chains {
+            assert!(chain.windows(2).all(|pair| pair[0].chars().last() == pair[1].chars().next()));
+            let letters: HashSet<char> = chain.iter().flat_map(|word| word.chars()).collect();
+            assert_eq!(letters.len(), 12);
+        }
+    }
+
+    #[test]
+    fn test_find_word_ladders() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+
+        let ladders = word_service.find_word_ladders("cold", "warm", &WordLadderOptions::new(true, false)).unwrap();
+
+        assert!(ladders.contains(&["cold", "cord", "card", "ward", "warm"].map(String::from).to_vec()));
+        assert!(ladders.iter().all(|ladder| ladder.len() == 5));
+
+        let ladders = word_service.find_word_ladders("cat", "dogs", &WordLadderOptions::new(false, true)).unwrap();
+        assert_eq!(ladders.len(), 1);
+        assert_eq!(ladders[0].len(), 5);
+        assert!(matches!(
+            word_service.find_word_ladders("cat", "xqzt", &WordLadderOptions::default()),
+            Err(Error::InvalidPuzzle(_))
+        ));
+    }
+
+    #[test]
+    fn test_solve_cryptogram() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        // Short words have many decodings, so as in a codeword a few letters are given,
+        // and the pangram is one of the keys found
+        let cryptogram = Cryptogram::from_text("GSV JFRXP YILDM ULC QFNKH LEVI GSV OZAB WLT")
+            .with_known('G', 't')
+            .with_known('J', 'q')
+            .with_known('Q', 'j')
+            .with_known('A', 'z');
+
+        let solutions = word_service.solve_cryptogram(&cryptogram, 1000).unwrap();
+
+        let expected = "the quick brown fox jumps over the lazy dog";
+        assert!(solutions.iter().any(|solution| solution.words.join(" ") == expected));
+        for solution in &solutions {
+            assert!(solution.words.iter().all(|word| word_service.dictionary.search(word)));
+            assert_eq!(solution.words[0], solution.words[6]);
+        }
+    }
+
+    #[test]
+    fn test_fill_crossword() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let grid = ["#....", "....#", "..#..", "#....", "....#"];
+
+        let fill = word_service.fill_crossword(&grid).unwrap().unwrap();
+
+        assert_eq!(fill.entries.len(), 12);
+        let words: HashSet<&str> = fill.entries.iter().map(|entry| entry.word.as_str()).collect();
+        assert_eq!(words.len(), fill.entries.len());
+        assert!(words.iter().all(|word| word_service.dictionary.search(word)));
+        for (row, (filled, pattern)) in fill.rows.iter().zip(grid).enumerate() {
+            for (column, (letter, cell)) in filled.chars().zip(pattern.chars()).enumerate() {
+                assert_eq!(letter == '#', cell == '#', "({}, {})", row, column);
+            }
+        }
+
+        let fill = word_service.fill_crossword(&["QU...", ".....", "....."]).unwrap().unwrap();
+        assert!(fill.rows[0].starts_with("qu"));
+        assert!(matches!(word_service.fill_crossword(&["..", "."]), Err(Error::RaggedGrid { .. })));
+    }
+
+    #[test]
+    fn test_solve_hangman() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+
+        let result = word_service.solve_hangman("_a__e_", "so").unwrap();
+
+        assert!(result.candidates.contains(&"banter".to_string()));
+        assert!(!result.candidates.contains(&"master".to_string()));
+        assert!(result.candidates.iter().all(|word| word.chars().filter(|&letter| letter == 'a').count() == 1));
+        assert!(result.suggestions.iter().all(|suggestion| !"aeso".contains(suggestion.letter)));
+        assert!(result.suggestions.windows(2).all(|pair| pair[0].words >= pair[1].words));
+
+        let result = word_service.solve_hangman("zebr_", "").unwrap();
+        assert_eq!(result.candidates, vec!["zebra"]);
+        assert_eq!(result.suggestions[0].letter, 'a');
+    }
+
+    #[test]
+    fn test_find_scrabble_moves() {
+        let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH).unwrap();
+        let letter_values = LetterValues::scrabble_english();
+        let moves = word_service.find_scrabble_moves(&ScrabbleBoard::new(), "JAM", &letter_values).unwrap();
+
+        // J8 A1 M3 doubled by the centre square
+        assert_eq!(moves[0].word, "jam");
+        assert_eq!(moves[0].score, 24);
+
+        let mut board = ScrabbleBoard::new();
+        board.play(&moves[0]).unwrap();
+        let moves = word_service.find_scrabble_moves(&board, "S", &letter_values).unwrap();
+        assert!(moves.iter().any(|found| found.word == "jams" && found.score == 13));
+
+        let result = word_service.find_scrabble_moves(&board, "S1", &letter_values);
+        assert!(matches!(result, Err(Error::NotInAlphabet('1'))));
+        let result = word_service.find_scrabble_moves(&board, "ABCDEFGH", &letter_values);
+        assert!(matches!(result, Err(Error::InvalidPuzzle(_))));
     }
 }
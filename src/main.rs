@@ -1,15 +1,22 @@
 use std::io::stdin;
-use word_unscrambler::{SCRABBLE_DICTIONARY_PATH, WordService};
+use std::process::exit;
+use word_unscrambler::{BasicWordFilters, SCRABBLE_DICTIONARY_PATH, WordService};
 
 fn main() {
-    let word_service = WordService::new(SCRABBLE_DICTIONARY_PATH);
+    let word_service = match WordService::new(SCRABBLE_DICTIONARY_PATH) {
+        Ok(word_service) => word_service,
+        Err(error) => {
+            eprintln!("Could not load dictionary: {}", error);
+            exit(1);
+        }
+    };
     println!("Welcome to Word Finder!");
     println!("Enter a word to find anagrams\n");
 
     let mut input = String::new();
     stdin().read_line(&mut input).expect("Unable to read input");
 
-    let words = word_service.find_anagrams(input.trim());
+    let words = word_service.find_anagrams(input.trim(), &BasicWordFilters::default());
 
     println!("\nInput: {}", input.trim());
     println!("Words Found: {}", words.len());
@@ -1,7 +1,109 @@
 use std::collections::HashSet;
 use std::rc::Rc;
 use crate::word_finder::{WordFilters, WordFinder};
+use crate::dictionaries::alphabet::Alphabet;
 use crate::dictionaries::Dictionary;
+use crate::error::Error;
+
+/// A blank tile, which can stand for any letter of the dictionary's alphabet.
+pub const BLANK: char = '?';
+
+/// A word found in a rack, along with the positions blank tiles were played at.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AnagramMatch {
+    pub word: String,
+    /// Character positions in `word` that were spelled with a blank.
+    pub blank_positions: Vec<usize>,
+}
+
+impl AnagramMatch {
+    /// The letters the blanks stood for, in the order they appear in the word.
+    pub fn blank_letters(&self) -> Vec<char> {
+        let letters: Vec<char> = self.word.chars().collect();
+        self.blank_positions.iter().map(|&position| letters[position]).collect()
+    }
+}
+
+/// The letters left to play: a count per alphabet slot plus the unused blanks.
+pub(crate) struct Rack {
+    counts: Vec<usize>,
+    blanks: usize,
+}
+
+impl Rack {
+    /// Letters outside the alphabet can never be played and are dropped,
+    /// `BLANK` included.
+    pub(crate) fn new(letters: &str, alphabet: &Alphabet) -> Rack {
+        let mut rack = Rack {
+            counts: vec![0; alphabet.len()],
+            blanks: 0,
+        };
+        for letter in letters.chars() {
+            if let Some(index) = alphabet.index_of(letter) {
+                rack.counts[index] += 1;
+            }
+        }
+        rack
+    }
+
+    /// Like [`Rack::new`], but each `BLANK` is a blank tile.
+    pub(crate) fn with_blanks(letters: &str, alphabet: &Alphabet) -> Rack {
+        let mut rack = Rack::new(letters, alphabet);
+        rack.blanks = letters.chars().filter(|&letter| letter == BLANK).count();
+        rack
+    }
+
+    /// Checks that every tile of a rack written with `BLANK`s can be played,
+    /// so that a mistyped letter is reported rather than silently dropped.
+    pub(crate) fn check_tiles(letters: &str, alphabet: &Alphabet) -> Result<(), Error> {
+        match letters.chars().find(|&letter| letter != BLANK && alphabet.index_of(letter).is_none()) {
+            Some(letter) => Err(Error::NotInAlphabet(letter)),
+            None => Ok(()),
+        }
+    }
+
+    /// Number of tiles left, blanks included.
+    pub(crate) fn len(&self) -> usize {
+        self.counts.iter().sum::<usize>() + self.blanks
+    }
+
+    /// Removes the letters at `slots` if the rack holds all of them, leaving
+    /// it untouched otherwise.
+    pub(crate) fn take(&mut self, slots: &[usize]) -> bool {
+        for (taken, &slot) in slots.iter().enumerate() {
+            if self.counts[slot] == 0 {
+                self.put_back(&slots[..taken]);
+                return false;
+            }
+            self.counts[slot] -= 1;
+        }
+        true
+    }
+
+    pub(crate) fn put_back(&mut self, slots: &[usize]) {
+        for &slot in slots {
+            self.counts[slot] += 1;
+        }
+    }
+
+    pub(crate) fn take_blank(&mut self) -> bool {
+        if self.blanks == 0 {
+            return false;
+        }
+        self.blanks -= 1;
+        true
+    }
+
+    pub(crate) fn put_back_blank(&mut self) {
+        self.blanks += 1;
+    }
+}
+
+struct AnagramFinderState {
+    current_word: String,
+    blank_positions: Vec<usize>,
+    matches: HashSet<AnagramMatch>,
+}
 
 pub(crate) struct AnagramWordFinder<T: Dictionary> {
     dictionary: Rc<T>,
@@ -12,36 +114,108 @@ impl<T: Dictionary> AnagramWordFinder<T> {
         AnagramWordFinder { dictionary }
     }
 
-    fn find_permutations<F: WordFilters>(
+    /// Finds every word that can be spelled from `rack`, where each `BLANK`
+    /// can stand for any letter. Each word is found once, with the blanks
+    /// played only for letters the rack has run out of. Fails if the rack
+    /// holds a letter outside the dictionary's alphabet.
+    pub(crate) fn search_with_blanks<F: WordFilters>(
         &self,
-        prefix: &str,
-        suffix: &str,
-        words: &mut HashSet<String>,
+        rack: &str,
+        filters: &F,
+    ) -> Result<HashSet<AnagramMatch>, Error> {
+        let alphabet = self.dictionary.alphabet();
+        Rack::check_tiles(rack, alphabet)?;
+        let mut rack = Rack::with_blanks(rack, alphabet);
+        Ok(self.search_rack(&mut rack, filters))
+    }
+
+    /// Finds every word that can be spelled from the tiles of `rack`, which
+    /// is left as it was found.
+    pub(crate) fn search_rack<F: WordFilters>(&self, rack: &mut Rack, filters: &F) -> HashSet<AnagramMatch> {
+        let mut state = AnagramFinderState {
+            current_word: String::new(),
+            blank_positions: Vec::new(),
+            matches: HashSet::new(),
+        };
+
+        let root = self.dictionary.root();
+        self.find_anagrams(&root, rack, &mut state, filters);
+        state.matches
+    }
+
+    /// Walks the dictionary from `node`, following only letters still in the
+    /// rack. Each distinct letter is tried once per level however many copies
+    /// of it the rack holds, so duplicate letters never repeat work. A blank
+    /// only stands for a letter with no copies left, so that a word is never
+    /// found again with its blanks and letters swapped.
+    fn find_anagrams<'a, F: WordFilters>(
+        &'a self,
+        node: &T::Node<'a>,
+        rack: &mut Rack,
+        state: &mut AnagramFinderState,
         filters: &F,
     ) {
-        if suffix.len() == 0 {
-            return;
-        } else {
-            for (index, char) in suffix.chars().enumerate() {
-                let new_prefix = format!("{}{}", prefix, char);
-                let new_suffix = format!("{}{}", &suffix[0..index], &suffix[index + 1..]);
-
-                if self.dictionary.starts_with(&new_prefix) {
-                    if filters.passes_filters(&new_prefix) && self.dictionary.search(&new_prefix) {
-                        words.insert(String::from(&new_prefix));
-                    }
-                    self.find_permutations(&new_prefix, &new_suffix, words, filters);
+        let letters = self.dictionary.alphabet().letters();
+
+        for (index, &letter) in letters.iter().enumerate() {
+            if rack.counts[index] == 0 {
+                continue;
+            }
+            if let Some(next) = self.dictionary.next(node, letter) {
+                rack.counts[index] -= 1;
+                state.current_word.push(letter);
+                self.visit(&next, rack, state, filters);
+                state.current_word.pop();
+                rack.counts[index] += 1;
+            }
+        }
+
+        if rack.blanks > 0 {
+            let position = state.current_word.chars().count();
+            for (index, &letter) in letters.iter().enumerate() {
+                if rack.counts[index] > 0 {
+                    continue;
+                }
+                if let Some(next) = self.dictionary.next(node, letter) {
+                    rack.blanks -= 1;
+                    state.current_word.push(letter);
+                    state.blank_positions.push(position);
+                    self.visit(&next, rack, state, filters);
+                    state.blank_positions.pop();
+                    state.current_word.pop();
+                    rack.blanks += 1;
                 }
             }
         }
     }
+
+    fn visit<'a, F: WordFilters>(
+        &'a self,
+        node: &T::Node<'a>,
+        rack: &mut Rack,
+        state: &mut AnagramFinderState,
+        filters: &F,
+    ) {
+        if !filters.may_match_prefix(&state.current_word) {
+            return;
+        }
+        if self.dictionary.is_word(node) && filters.passes_filters(&state.current_word) {
+            state.matches.insert(AnagramMatch {
+                word: state.current_word.clone(),
+                blank_positions: state.blank_positions.clone(),
+            });
+        }
+        self.find_anagrams(node, rack, state, filters);
+    }
 }
 
 impl<T: Dictionary> WordFinder<String> for AnagramWordFinder<T> {
     fn search<F: WordFilters>(&self, word: &String, filters: &F) -> HashSet<String> {
-        let mut words = HashSet::new();
-        self.find_permutations("", word, &mut words, filters);
-        words
+        let mut rack = Rack::new(word, self.dictionary.alphabet());
+        self.search_rack(&mut rack, filters)
+            .into_iter()
+            .map(|anagram_match| anagram_match.word)
+            .collect()
     }
 }
 
@@ -50,7 +224,27 @@ impl<T: Dictionary> WordFinder<String> for AnagramWordFinder<T> {
 mod test {
     use super::*;
     use std::time::Instant;
-    use crate::word_finder::BasicWordFilters;
+    use crate::SCRABBLE_DICTIONARY_PATH;
+    use crate::dictionaries::tries::Trie;
+    use crate::dictionaries::tries::dawg::Dawg;
+    use crate::dictionaries::tries::trie_builder::{TrieBuilder, TxtFileTrieBuilder};
+    use crate::word_finder::{BasicWordFilters, WithoutPruning};
+
+    /// The previous search, which permuted the rack as strings and relied on
+    /// the set to drop the duplicates produced by repeated letters.
+    fn find_permutations<T: Dictionary>(dictionary: &T, prefix: &str, suffix: &str, words: &mut HashSet<String>) {
+        for (index, char) in suffix.char_indices() {
+            let new_prefix = format!("{}{}", prefix, char);
+            let new_suffix = format!("{}{}", &suffix[0..index], &suffix[index + char.len_utf8()..]);
+
+            if dictionary.starts_with(&new_prefix) {
+                if dictionary.search(&new_prefix) {
+                    words.insert(String::from(&new_prefix));
+                }
+                find_permutations(dictionary, &new_prefix, &new_suffix, words);
+            }
+        }
+    }
 
     #[test]
     fn test_anagram_word_finder_search() {
@@ -86,4 +280,148 @@ mod test {
         assert!(words.contains("traction"));
         assert!(!words.contains("electricity"));
     }
+
+    #[test]
+    fn test_anagram_word_finder_search_with_blanks() {
+        let dictionary = HashSet::from([
+            "cat".to_string(),
+            "act".to_string(),
+            "cot".to_string(),
+            "taco".to_string(),
+            "at".to_string(),
+            "dog".to_string(),
+        ]);
+
+        let anagram_word_finder = AnagramWordFinder::new(Rc::new(dictionary));
+        let filters: BasicWordFilters = Default::default();
+        let matches = anagram_word_finder.search_with_blanks("c?t", &filters).unwrap();
+        let words: HashSet<&str> = matches.iter().map(|m| m.word.as_str()).collect();
+
+        assert_eq!(words, HashSet::from(["cat", "act", "cot", "at"]));
+        assert!(matches.contains(&AnagramMatch { word: "cot".to_string(), blank_positions: vec![1] }));
+        assert!(matches.contains(&AnagramMatch { word: "act".to_string(), blank_positions: vec![0] }));
+        assert_eq!(
+            matches.iter().find(|m| m.word == "cot").unwrap().blank_letters(),
+            vec!['o']
+        );
+        assert!(!words.contains("taco"));
+
+        // Letters still in the rack are played before the blanks
+        let matches = anagram_word_finder.search_with_blanks("at??", &filters).unwrap();
+        let cat: Vec<&AnagramMatch> = matches.iter().filter(|m| m.word == "cat").collect();
+        assert_eq!(cat, vec![&AnagramMatch { word: "cat".to_string(), blank_positions: vec![0] }]);
+        assert!(matches.contains(&AnagramMatch { word: "taco".to_string(), blank_positions: vec![2, 3] }));
+        assert_eq!(anagram_word_finder.search(&"c?t".to_string(), &filters), HashSet::new());
+
+        assert!(matches!(
+            anagram_word_finder.search_with_blanks("c1t?", &filters),
+            Err(Error::NotInAlphabet('1'))
+        ));
+    }
+
+    #[test]
+    fn test_anagram_word_finder_search_long_rack() {
+        let dictionary = HashSet::from(["aa".to_string(), "aaa".to_string(), "ab".to_string()]);
+        let anagram_word_finder = AnagramWordFinder::new(Rc::new(dictionary));
+        let filters: BasicWordFilters = Default::default();
+
+        // More copies of a letter than fit in a byte
+        let rack = "a".repeat(300);
+        let words = anagram_word_finder.search(&rack, &filters);
+        assert_eq!(words, HashSet::from(["aa".to_string(), "aaa".to_string()]));
+        assert_eq!(Rack::with_blanks(&format!("{}??", rack), &Alphabet::english()).len(), 302);
+    }
+
+    #[test]
+    fn test_anagram_word_finder_search_with_alphabet() {
+        let mut dawg = Dawg::with_alphabet(Alphabet::french().with_accent_folding());
+        for word in ["café", "face", "fée"] {
+            dawg.insert(word).unwrap();
+        }
+        dawg.finish();
+
+        let anagram_word_finder = AnagramWordFinder::new(Rc::new(dawg));
+        let filters: BasicWordFilters = Default::default();
+        let words = anagram_word_finder.search(&String::from("ÉFAC"), &filters);
+
+        assert_eq!(words, HashSet::from(["cafe".to_string(), "face".to_string()]));
+    }
+
+    #[test]
+    fn test_multiset_search_matches_string_permutations() {
+        let dictionary: HashSet<String> = ["see", "seen", "sene", "nee", "ness", "sense", "senses", "esne", "ens", "es"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+        let anagram_word_finder = AnagramWordFinder::new(Rc::new(dictionary.clone()));
+        let filters: BasicWordFilters = Default::default();
+
+        for rack in ["senses", "essence", "nesses"] {
+            let mut expected = HashSet::new();
+            find_permutations(&dictionary, "", rack, &mut expected);
+            assert_eq!(anagram_word_finder.search(&rack.to_string(), &filters), expected);
+        }
+    }
+
+    /// Compares the search times on the full dictionary, run with `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn test_multiset_search_compared_to_string_permutations() {
+        let mut dawg = Dawg::new();
+        TxtFileTrieBuilder::new(SCRABBLE_DICTIONARY_PATH).unwrap().build(&mut dawg).unwrap();
+        let dawg = Rc::new(dawg);
+        let anagram_word_finder = AnagramWordFinder::new(Rc::clone(&dawg));
+        let filters: BasicWordFilters = Default::default();
+
+        for rack in ["championships", "interrelations", "electrification"] {
+            let start_time = Instant::now();
+            let mut expected = HashSet::new();
+            find_permutations(dawg.as_ref(), "", rack, &mut expected);
+            let permutations_time = Instant::now() - start_time;
+
+            let start_time = Instant::now();
+            let words = anagram_word_finder.search(&rack.to_string(), &filters);
+            let multiset_time = Instant::now() - start_time;
+
+            println!(
+                "{} letters ({}): string permutations {:?}, multiset {:?}, {} words",
+                rack.len(),
+                rack,
+                permutations_time,
+                multiset_time,
+                words.len()
+            );
+            assert_eq!(words, expected);
+        }
+    }
+
+    #[test]
+    fn test_prefix_pruning_compared_to_word_filtering() {
+        let mut dawg = Dawg::new();
+        TxtFileTrieBuilder::new(SCRABBLE_DICTIONARY_PATH).unwrap().build(&mut dawg).unwrap();
+        let anagram_word_finder = AnagramWordFinder::new(Rc::new(dawg));
+
+        for (rack, prefix) in [("quintessential", "qu"), ("interrelations", "str"), ("electrification", "fl")] {
+            let filters = BasicWordFilters::default().starts_with(prefix);
+
+            let start_time = Instant::now();
+            let expected = anagram_word_finder.search(&rack.to_string(), &WithoutPruning(filters.clone()));
+            let filtering_time = Instant::now() - start_time;
+
+            let start_time = Instant::now();
+            let words = anagram_word_finder.search(&rack.to_string(), &filters);
+            let pruning_time = Instant::now() - start_time;
+
+            println!(
+                "{} starting with {}: word filtering {:?}, prefix pruning {:?}, {} words",
+                rack,
+                prefix,
+                filtering_time,
+                pruning_time,
+                words.len()
+            );
+            assert_eq!(words, expected);
+            assert!(!words.is_empty());
+        }
+    }
 }
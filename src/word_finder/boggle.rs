@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::word_finder::matrix::Matrix;
+use crate::word_finder::matrix_word_finder::MatrixWordFinder;
+use crate::word_finder::{BasicWordFilters, WordFinder};
+
+/// Minimum word length and point table for a game of Boggle.
+#[derive(Clone, Debug)]
+pub struct BoggleRules {
+    pub min_word_length: usize,
+    /// Points for a word of each length, indexed by length. Words longer
+    /// than the table score its last entry.
+    pub points_by_length: Vec<u32>,
+}
+
+impl BoggleRules {
+    pub fn new(min_word_length: usize, points_by_length: Vec<u32>) -> BoggleRules {
+        BoggleRules {
+            min_word_length,
+            points_by_length,
+        }
+    }
+
+    /// Classic 4x4 Boggle: words of three or more letters.
+    pub fn classic() -> BoggleRules {
+        BoggleRules::new(3, vec![0, 0, 0, 1, 1, 2, 3, 5, 11])
+    }
+
+    /// Big Boggle on a 5x5 board, where three letter words do not count.
+    pub fn big_boggle() -> BoggleRules {
+        BoggleRules::new(4, vec![0, 0, 0, 0, 1, 2, 3, 5, 11])
+    }
+
+    /// Points scored by `word`. Multi-letter tiles count every letter, so
+    /// "queen" spelled with a "Qu" die is a five letter word.
+    pub fn score(&self, word: &str) -> u32 {
+        let length = word.chars().count();
+        if length < self.min_word_length {
+            return 0;
+        }
+        match self.points_by_length.get(length) {
+            Some(&points) => points,
+            None => self.points_by_length.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl Default for BoggleRules {
+    fn default() -> BoggleRules {
+        BoggleRules::classic()
+    }
+}
+
+/// The words found on a Boggle board with their points.
+#[derive(Debug, Default)]
+pub struct BoggleResult {
+    pub words: HashMap<String, u32>,
+    pub total_score: u32,
+}
+
+pub(crate) struct BoggleWordFinder<T: Dictionary> {
+    matrix_word_finder: MatrixWordFinder<T>,
+}
+
+impl<T: Dictionary> BoggleWordFinder<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> BoggleWordFinder<T> {
+        BoggleWordFinder {
+            matrix_word_finder: MatrixWordFinder::new(dictionary, true),
+        }
+    }
+
+    pub(crate) fn search(&self, board: &Matrix, rules: &BoggleRules) -> BoggleResult {
+        let filters = BasicWordFilters::default().min_length(rules.min_word_length);
+        let words: HashMap<String, u32> = self
+            .matrix_word_finder
+            .search(board, &filters)
+            .into_iter()
+            .map(|word| {
+                let points = rules.score(&word);
+                (word, points)
+            })
+            .collect();
+
+        BoggleResult {
+            total_score: words.values().sum(),
+            words,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+
+    #[test]
+    fn test_boggle_rules_score() {
+        let rules = BoggleRules::classic();
+
+        assert_eq!(rules.score("at"), 0);
+        assert_eq!(rules.score("cat"), 1);
+        assert_eq!(rules.score("cats"), 1);
+        assert_eq!(rules.score("quite"), 2);
+        assert_eq!(rules.score("quartz"), 3);
+        assert_eq!(rules.score("quality"), 5);
+        assert_eq!(rules.score("quarters"), 11);
+        assert_eq!(rules.score("questioning"), 11);
+        assert_eq!(BoggleRules::big_boggle().score("cat"), 0);
+    }
+
+    #[test]
+    fn test_boggle_word_finder_4x4_board() {
+        let board = Matrix::from_tiles(&[
+            &["Qu", "I", "T", "E"],
+            &["A", "R", "S", "N"],
+            &["T", "E", "L", "O"],
+            &["D", "G", "H", "P"],
+        ]).unwrap();
+        let dictionary = test_dictionary(&["quit", "quite", "quits", "quiet", "rest", "sent", "it", "zebra"]);
+        let boggle_word_finder = BoggleWordFinder::new(dictionary);
+
+        let result = boggle_word_finder.search(&board, &BoggleRules::classic());
+
+        assert_eq!(
+            result.words,
+            HashMap::from([
+                ("quit".to_string(), 1),
+                ("quite".to_string(), 2),
+                ("quits".to_string(), 2),
+                ("rest".to_string(), 1),
+                ("sent".to_string(), 1),
+            ])
+        );
+        assert_eq!(result.total_score, 7);
+    }
+
+    #[test]
+    fn test_boggle_word_finder_5x5_board() {
+        let board = Matrix::from_tiles(&[
+            &["S", "T", "A", "R", "E"],
+            &["L", "I", "N", "G", "O"],
+            &["P", "E", "A", "C", "H"],
+            &["M", "O", "U", "S", "E"],
+            &["D", "R", "I", "V", "E"],
+        ]).unwrap();
+        let dictionary = test_dictionary(&[
+            "star", "stare", "linger", "peach", "mouse", "drive", "pea", "each", "ache", "cause", "mice",
+        ]);
+        let boggle_word_finder = BoggleWordFinder::new(dictionary);
+
+        let result = boggle_word_finder.search(&board, &BoggleRules::big_boggle());
+
+        assert_eq!(result.words.len(), 9);
+        assert_eq!(result.words["linger"], 3);
+        assert_eq!(result.words["cause"], 2);
+        assert_eq!(result.words["each"], 1);
+        assert!(!result.words.contains_key("pea"));
+        assert!(!result.words.contains_key("mice"));
+        assert_eq!(result.total_score, 16);
+    }
+}
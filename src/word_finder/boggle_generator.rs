@@ -0,0 +1,345 @@
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+use crate::word_finder::boggle::{BoggleResult, BoggleRules, BoggleWordFinder};
+use crate::word_finder::matrix::Matrix;
+
+/// Where the tiles of a random board come from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoggleLetters {
+    /// One die per cell: the dice are shuffled over the board and each shows
+    /// one of its faces.
+    Dice(Vec<Vec<String>>),
+    /// Every cell is drawn on its own, each tile as likely as its weight.
+    Frequencies(Vec<(String, u32)>),
+}
+
+impl BoggleLetters {
+    /// Reads dice written as their faces, e.g. "AAEEGN". A `Q` face is the
+    /// "Qu" tile, as on real Boggle dice.
+    pub fn from_dice(dice: &[&str]) -> BoggleLetters {
+        BoggleLetters::Dice(dice.iter().map(|faces| faces.chars().map(tile).collect()).collect())
+    }
+
+    /// Reads letters with their weights, `Q` again standing for "Qu".
+    pub fn from_frequencies(frequencies: &[(char, u32)]) -> BoggleLetters {
+        BoggleLetters::Frequencies(frequencies.iter().map(|&(letter, weight)| (tile(letter), weight)).collect())
+    }
+
+    /// The sixteen dice of classic 4x4 Boggle.
+    pub fn classic_dice() -> BoggleLetters {
+        BoggleLetters::from_dice(&[
+            "AAEEGN", "ABBJOO", "ACHOPS", "AFFKPS", "AOOTTW", "CIMOTU", "DEILRX", "DELRVY", "DISTTY", "EEGHNW",
+            "EEINSU", "EHRTVW", "EIOSST", "ELRTTY", "HIMNUQ", "HLNNRZ",
+        ])
+    }
+
+    /// The twenty-five dice of Big Boggle, played on a 5x5 board.
+    pub fn big_boggle_dice() -> BoggleLetters {
+        BoggleLetters::from_dice(&[
+            "AAAFRS", "AAEEEE", "AAFIRS", "ADENNN", "AEEEEM", "AEEGMU", "AEGMNN", "AFIRSY", "BJKQXZ", "CCENST",
+            "CEIILT", "CEILPT", "CEIPST", "DDHNOT", "DHHLOR", "DHLNOR", "DHLNOR", "EIIITT", "EMOTTT", "ENSSSU",
+            "FIPRSY", "GORRVW", "IPRRRY", "NOOTUW", "OOOTTU",
+        ])
+    }
+
+    /// How often each letter appears in English text, per thousand letters.
+    pub fn english_frequencies() -> BoggleLetters {
+        BoggleLetters::from_frequencies(&[
+            ('E', 127), ('T', 91), ('A', 82), ('O', 75), ('I', 70), ('N', 67), ('S', 63), ('H', 61), ('R', 60),
+            ('D', 43), ('L', 40), ('C', 28), ('U', 28), ('M', 24), ('W', 24), ('F', 22), ('G', 20), ('Y', 20),
+            ('P', 19), ('B', 15), ('V', 10), ('K', 8), ('J', 2), ('X', 2), ('Q', 1), ('Z', 1),
+        ])
+    }
+}
+
+fn tile(letter: char) -> String {
+    match letter {
+        'Q' => "Qu".to_string(),
+        letter => letter.to_string(),
+    }
+}
+
+/// What a generated board has to offer, built up one condition at a time,
+/// e.g. `BoggleConstraints::default().min_words(100).long_word(8)`.
+#[derive(Clone, Debug)]
+pub struct BoggleConstraints {
+    min_words: usize,
+    long_word: Option<usize>,
+    min_score: u32,
+    max_score: Option<u32>,
+    max_attempts: usize,
+}
+
+impl Default for BoggleConstraints {
+    fn default() -> BoggleConstraints {
+        BoggleConstraints {
+            min_words: 0,
+            long_word: None,
+            min_score: 0,
+            max_score: None,
+            max_attempts: 1000,
+        }
+    }
+}
+
+impl BoggleConstraints {
+    /// Fewest words the board must have.
+    pub fn min_words(mut self, min_words: usize) -> BoggleConstraints {
+        self.min_words = min_words;
+        self
+    }
+
+    /// The board must have a word of at least `length` letters.
+    pub fn long_word(mut self, length: usize) -> BoggleConstraints {
+        self.long_word = Some(length);
+        self
+    }
+
+    /// The total score of the board must be between `min` and `max`, inclusive.
+    pub fn score_range(mut self, min: u32, max: u32) -> BoggleConstraints {
+        self.min_score = min;
+        self.max_score = Some(max);
+        self
+    }
+
+    /// Boards generated before giving up, 1000 unless set.
+    pub fn max_attempts(mut self, max_attempts: usize) -> BoggleConstraints {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn accepts(&self, result: &BoggleResult) -> bool {
+        result.words.len() >= self.min_words
+            && self
+                .long_word
+                .is_none_or(|length| result.words.keys().any(|word| word.chars().count() >= length))
+            && result.total_score >= self.min_score
+            && self.max_score.is_none_or(|max_score| result.total_score <= max_score)
+    }
+}
+
+/// A board meeting the constraints it was generated for, with its words.
+#[derive(Debug)]
+pub struct GeneratedBoggleBoard {
+    pub board: Vec<Vec<String>>,
+    pub result: BoggleResult,
+    /// Boards generated to find this one, counting itself.
+    pub attempts: usize,
+}
+
+/// A small SplitMix64 generator, so that a seed always gives the same
+/// boards on every platform.
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Random {
+        Random { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        value ^ (value >> 31)
+    }
+
+    /// A number from 0 up to but not including `bound`.
+    fn below(&mut self, bound: usize) -> usize {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+}
+
+/// Generates random Boggle boards until one meets a set of constraints,
+/// solving each with the Boggle word finder.
+pub(crate) struct BoggleBoardGenerator<T: Dictionary> {
+    boggle_word_finder: BoggleWordFinder<T>,
+}
+
+impl<T: Dictionary> BoggleBoardGenerator<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> BoggleBoardGenerator<T> {
+        BoggleBoardGenerator {
+            boggle_word_finder: BoggleWordFinder::new(dictionary),
+        }
+    }
+
+    /// Returns the first `size` x `size` board drawn from `letters` whose
+    /// words under `rules` meet `constraints`, or `None` if none does within
+    /// the allowed attempts. The same seed always gives the same board.
+    pub(crate) fn generate(
+        &self,
+        seed: u64,
+        size: usize,
+        letters: &BoggleLetters,
+        rules: &BoggleRules,
+        constraints: &BoggleConstraints,
+    ) -> Result<Option<GeneratedBoggleBoard>, Error> {
+        let mut random = Random::new(seed);
+        for attempts in 1..=constraints.max_attempts {
+            let board = roll_board(&mut random, size, letters)?;
+            let rows: Vec<Vec<&str>> = board.iter().map(|row| row.iter().map(String::as_str).collect()).collect();
+            let rows: Vec<&[&str]> = rows.iter().map(Vec::as_slice).collect();
+            let matrix = Matrix::from_tiles(&rows)?;
+
+            let result = self.boggle_word_finder.search(&matrix, rules);
+            if constraints.accepts(&result) {
+                return Ok(Some(GeneratedBoggleBoard {
+                    board,
+                    result,
+                    attempts,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn roll_board(random: &mut Random, size: usize, letters: &BoggleLetters) -> Result<Vec<Vec<String>>, Error> {
+    let tiles: Vec<String> = match letters {
+        BoggleLetters::Dice(dice) => {
+            if dice.len() != size * size {
+                return Err(Error::InvalidPuzzle(format!("{} dice for a {}x{} board", dice.len(), size, size)));
+            }
+            let mut order: Vec<usize> = (0..dice.len()).collect();
+            for index in (1..order.len()).rev() {
+                order.swap(index, random.below(index + 1));
+            }
+            order
+                .into_iter()
+                .map(|die| {
+                    let faces = &dice[die];
+                    if faces.is_empty() {
+                        return Err(Error::InvalidPuzzle(format!("die {} has no faces", die)));
+                    }
+                    Ok(faces[random.below(faces.len())].clone())
+                })
+                .collect::<Result<Vec<String>, Error>>()?
+        }
+        BoggleLetters::Frequencies(frequencies) => {
+            let total: u64 = frequencies.iter().map(|&(_, weight)| u64::from(weight)).sum();
+            if total == 0 {
+                return Err(Error::InvalidPuzzle("no letter has a weight".to_string()));
+            }
+            (0..size * size)
+                .map(|_| {
+                    let mut drawn = random.below(total as usize) as u64;
+                    let (tile, _) = frequencies
+                        .iter()
+                        .find(|&&(_, weight)| {
+                            let found = drawn < u64::from(weight);
+                            drawn = drawn.saturating_sub(u64::from(weight));
+                            found
+                        })
+                        .unwrap();
+                    tile.clone()
+                })
+                .collect()
+        }
+    };
+    Ok(tiles.chunks(size.max(1)).map(<[String]>::to_vec).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+    use std::collections::HashSet;
+
+    fn board_generator(words: &[&str]) -> BoggleBoardGenerator<HashSet<String>> {
+        BoggleBoardGenerator::new(test_dictionary(words))
+    }
+
+    #[test]
+    fn test_random_is_seeded() {
+        let mut first = Random::new(42);
+        let mut second = Random::new(42);
+        let first_values: Vec<u64> = (0..5).map(|_| first.next_u64()).collect();
+        let second_values: Vec<u64> = (0..5).map(|_| second.next_u64()).collect();
+
+        assert_eq!(first_values, second_values);
+        assert_ne!(first_values, (0..5).map(|_| Random::new(43).next_u64()).collect::<Vec<u64>>());
+        assert!((0..1000).all(|_| first.below(6) < 6));
+    }
+
+    #[test]
+    fn test_roll_dice() {
+        let letters = BoggleLetters::classic_dice();
+        let BoggleLetters::Dice(dice) = &letters else {
+            unreachable!();
+        };
+        assert_eq!(dice[14], vec!["H", "I", "M", "N", "U", "Qu"]);
+
+        let board = roll_board(&mut Random::new(7), 4, &letters).unwrap();
+        assert_eq!(board.len(), 4);
+        assert!(board.iter().all(|row| row.len() == 4));
+        assert_eq!(board, roll_board(&mut Random::new(7), 4, &letters).unwrap());
+        assert!(matches!(roll_board(&mut Random::new(7), 5, &letters), Err(Error::InvalidPuzzle(_))));
+
+        // Every die shows up exactly once
+        let letters = BoggleLetters::from_dice(&["AE", "BF", "CG", "DQ"]);
+        let BoggleLetters::Dice(dice) = &letters else {
+            unreachable!();
+        };
+        for seed in 0..10 {
+            let board = roll_board(&mut Random::new(seed), 2, &letters).unwrap();
+            let mut rolled: Vec<usize> = board
+                .iter()
+                .flatten()
+                .map(|tile| dice.iter().position(|faces| faces.contains(tile)).unwrap())
+                .collect();
+            rolled.sort();
+            assert_eq!(rolled, vec![0, 1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn test_roll_frequencies() {
+        let letters = BoggleLetters::from_frequencies(&[('A', 3), ('Q', 1), ('Z', 0)]);
+        let board = roll_board(&mut Random::new(1), 10, &letters).unwrap();
+        let tiles: Vec<&String> = board.iter().flatten().collect();
+
+        assert_eq!(tiles.len(), 100);
+        assert!(tiles.iter().all(|tile| *tile == "A" || *tile == "Qu"));
+        let a_tiles = tiles.iter().filter(|tile| **tile == "A").count();
+        assert!((50..95).contains(&a_tiles));
+
+        let letters = BoggleLetters::from_frequencies(&[('Z', 0)]);
+        assert!(matches!(roll_board(&mut Random::new(1), 4, &letters), Err(Error::InvalidPuzzle(_))));
+    }
+
+    #[test]
+    fn test_constraints() {
+        let result = BoggleResult {
+            words: [("cat".to_string(), 1), ("quartz".to_string(), 3)].into_iter().collect(),
+            total_score: 4,
+        };
+
+        assert!(BoggleConstraints::default().accepts(&result));
+        assert!(BoggleConstraints::default().min_words(2).long_word(6).accepts(&result));
+        assert!(!BoggleConstraints::default().min_words(3).accepts(&result));
+        assert!(!BoggleConstraints::default().long_word(7).accepts(&result));
+        assert!(BoggleConstraints::default().score_range(4, 4).accepts(&result));
+        assert!(!BoggleConstraints::default().score_range(5, 10).accepts(&result));
+        assert!(!BoggleConstraints::default().score_range(0, 3).accepts(&result));
+    }
+
+    #[test]
+    fn test_generate() {
+        let generator = board_generator(&["aa", "aaa", "aaaa"]);
+        let letters = BoggleLetters::from_frequencies(&[('A', 1), ('B', 1)]);
+        let rules = BoggleRules::classic();
+
+        let constraints = BoggleConstraints::default().long_word(4);
+        let generated = generator.generate(3, 2, &letters, &rules, &constraints).unwrap().unwrap();
+        assert_eq!(generated.board, vec![vec!["A", "A"], vec!["A", "A"]]);
+        assert_eq!(generated.result.total_score, 2);
+        let again = generator.generate(3, 2, &letters, &rules, &constraints).unwrap().unwrap();
+        assert_eq!((again.board, again.attempts), (generated.board, generated.attempts));
+
+        let constraints = BoggleConstraints::default().min_words(3).max_attempts(50);
+        assert!(generator.generate(3, 2, &letters, &rules, &constraints).unwrap().is_none());
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{HashSet, VecDeque};
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+use crate::word_finder::matrix::Direction::{Down, Left, Right, Up};
+use crate::word_finder::matrix::{Direction, Matrix, Point};
+use crate::word_finder::pattern_word_finder::{Pattern, PatternWordFinder};
+
+/// A word of a filled crossword, read across (`Right`) or `Down` from `start`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrosswordEntry {
+    pub start: Point,
+    pub direction: Direction,
+    pub word: String,
+}
+
+/// A filled crossword: its rows, with `#` for blocked cells, and its
+/// entries, the across ones first, each in reading order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrosswordFill {
+    pub rows: Vec<String>,
+    pub entries: Vec<CrosswordEntry>,
+}
+
+/// A run of two or more open cells, which has to be filled with a word.
+struct Slot {
+    start: Point,
+    direction: Direction,
+    /// The index of each cell in the matrix.
+    cells: Vec<usize>,
+    /// The other slots sharing a cell with this one, with the position of
+    /// that cell in this slot and in the other.
+    crossings: Vec<(usize, usize, usize)>,
+}
+
+struct CrosswordState {
+    slots: Vec<Slot>,
+    /// The dictionary words matching each slot's pre-filled letters.
+    words: Vec<Vec<Vec<char>>>,
+    assigned: Vec<bool>,
+    used: HashSet<Vec<char>>,
+}
+
+impl CrosswordState {
+    /// Removes the words that no longer fit the words left for the slots
+    /// crossing them, until every word left fits or a slot has none. Keeps
+    /// going from the slots in `queue` until nothing else changes.
+    fn propagate(&self, domains: &mut [Vec<usize>], mut queue: VecDeque<usize>) -> bool {
+        while let Some(slot) = queue.pop_front() {
+            for &(other, position, other_position) in &self.slots[slot].crossings {
+                let letters: HashSet<char> =
+                    domains[slot].iter().map(|&word| self.words[slot][word][position]).collect();
+                let before = domains[other].len();
+                domains[other].retain(|&word| letters.contains(&self.words[other][word][other_position]));
+                if domains[other].is_empty() {
+                    return false;
+                }
+                if domains[other].len() != before && !queue.contains(&other) {
+                    queue.push_back(other);
+                }
+            }
+        }
+        true
+    }
+
+    /// The unfilled slot with the fewest words left, preferring longer slots.
+    fn most_constrained(&self, domains: &[Vec<usize>]) -> Option<usize> {
+        (0..self.slots.len())
+            .filter(|&slot| !self.assigned[slot])
+            .min_by_key(|&slot| (domains[slot].len(), std::cmp::Reverse(self.slots[slot].cells.len())))
+    }
+}
+
+/// Fills crossword grids by picking a word for the most constrained slot,
+/// propagating its letters to the slots crossing it and backtracking when
+/// one of them runs out of words.
+pub(crate) struct CrosswordFiller<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> CrosswordFiller<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> CrosswordFiller<T> {
+        CrosswordFiller { dictionary }
+    }
+
+    /// Fills every slot of `matrix` with a different word, keeping its
+    /// pre-filled letters, or returns `None` if the grid cannot be filled.
+    /// Open cells outside any slot are left as `.` unless pre-filled.
+    pub(crate) fn fill(&self, matrix: &Matrix) -> Result<Option<CrosswordFill>, Error> {
+        let mut letters = self.read_letters(matrix)?;
+        let slots = find_slots(matrix);
+        let pattern_finder = PatternWordFinder::new(Rc::clone(&self.dictionary));
+        let mut words = Vec::new();
+        for slot in &slots {
+            let pattern: String = slot.cells.iter().map(|&cell| letters[cell].unwrap_or('?')).collect();
+            let pattern = Pattern::parse(&pattern, self.dictionary.alphabet())?;
+            let candidates = pattern_finder.search_pattern(&pattern);
+            words.push(candidates.iter().map(|word| word.chars().collect()).collect());
+        }
+
+        let mut state = CrosswordState {
+            assigned: vec![false; slots.len()],
+            used: HashSet::new(),
+            slots,
+            words,
+        };
+        let mut domains: Vec<Vec<usize>> = state.words.iter().map(|words| (0..words.len()).collect()).collect();
+        if domains.iter().any(Vec::is_empty) || !state.propagate(&mut domains, (0..state.slots.len()).collect()) {
+            return Ok(None);
+        }
+        let Some(domains) = fill_next_slot(&mut state, domains) else {
+            return Ok(None);
+        };
+
+        let mut entries = Vec::new();
+        for (index, slot) in state.slots.iter().enumerate() {
+            let word = &state.words[index][domains[index][0]];
+            for (&cell, &letter) in slot.cells.iter().zip(word) {
+                letters[cell] = Some(letter);
+            }
+            entries.push(CrosswordEntry {
+                start: slot.start,
+                direction: slot.direction,
+                word: word.iter().collect(),
+            });
+        }
+        let rows = letters
+            .chunks(matrix.get_width())
+            .enumerate()
+            .map(|(row, cells)| {
+                cells
+                    .iter()
+                    .enumerate()
+                    .map(|(column, letter)| match letter {
+                        _ if matrix.is_blocked((row, column)) => '#',
+                        Some(letter) => *letter,
+                        None => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Some(CrosswordFill { rows, entries }))
+    }
+
+    /// The pre-filled letter of each cell, normalized by the alphabet.
+    fn read_letters(&self, matrix: &Matrix) -> Result<Vec<Option<char>>, Error> {
+        let alphabet = self.dictionary.alphabet();
+        (0..matrix.get_capacity())
+            .map(|index| {
+                let point = matrix.get_point_from_index(index);
+                let tile = matrix.get_tile(point);
+                let mut letters = tile.chars();
+                match (letters.next(), letters.next()) {
+                    (None, _) => Ok(None),
+                    (Some(letter), None) => alphabet.normalize(letter).map(Some).ok_or(Error::NotInAlphabet(letter)),
+                    _ => Err(Error::InvalidPuzzle(format!("cell {:?} holds more than one letter: {}", point, tile))),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Finds the across slots, then the down ones, in reading order, and where
+/// they cross.
+fn find_slots(matrix: &Matrix) -> Vec<Slot> {
+    let mut slots = Vec::new();
+    for (direction, backwards) in [(Right, Left), (Down, Up)] {
+        for index in 0..matrix.get_capacity() {
+            let start = matrix.get_point_from_index(index);
+            if matrix.is_blocked(start) || matrix.next(start, &backwards).is_some() {
+                continue;
+            }
+            let mut cells = vec![index];
+            let mut point = start;
+            while let Some(next) = matrix.next(point, &direction) {
+                cells.push(matrix.get_index_from_point(next));
+                point = next;
+            }
+            if cells.len() >= 2 {
+                slots.push(Slot {
+                    start,
+                    direction,
+                    cells,
+                    crossings: Vec::new(),
+                });
+            }
+        }
+    }
+
+    let mut cell_slots: Vec<Vec<(usize, usize)>> = vec![Vec::new(); matrix.get_capacity()];
+    for (slot, Slot { cells, .. }) in slots.iter().enumerate() {
+        for (position, &cell) in cells.iter().enumerate() {
+            cell_slots[cell].push((slot, position));
+        }
+    }
+    for crossing in cell_slots {
+        if let [(first, first_position), (second, second_position)] = crossing[..] {
+            slots[first].crossings.push((second, first_position, second_position));
+            slots[second].crossings.push((first, second_position, first_position));
+        }
+    }
+    slots
+}
+
+/// Tries each word left for the most constrained slot, returning the
+/// domains of the first complete fill, which hold a single word per slot.
+fn fill_next_slot(state: &mut CrosswordState, domains: Vec<Vec<usize>>) -> Option<Vec<Vec<usize>>> {
+    let Some(slot) = state.most_constrained(&domains) else {
+        return Some(domains);
+    };
+
+    state.assigned[slot] = true;
+    for &word in &domains[slot] {
+        let letters = &state.words[slot][word];
+        if state.used.contains(letters) {
+            continue;
+        }
+        let mut next_domains = domains.clone();
+        next_domains[slot] = vec![word];
+        for (other, domain) in next_domains.iter_mut().enumerate() {
+            if !state.assigned[other] && state.slots[other].cells.len() == letters.len() {
+                domain.retain(|&other_word| state.words[other][other_word] != *letters);
+            }
+        }
+        if next_domains.iter().any(Vec::is_empty) || !state.propagate(&mut next_domains, VecDeque::from([slot])) {
+            continue;
+        }
+
+        let letters = letters.clone();
+        state.used.insert(letters.clone());
+        if let Some(filled) = fill_next_slot(state, next_domains) {
+            return Some(filled);
+        }
+        state.used.remove(&letters);
+    }
+    state.assigned[slot] = false;
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+
+    fn crossword_filler(words: &[&str]) -> CrosswordFiller<HashSet<String>> {
+        CrosswordFiller::new(test_dictionary(words))
+    }
+
+    #[test]
+    fn test_find_slots() {
+        let matrix = Matrix::with_blocks(&["...#", ".#..", "...."]).unwrap();
+        let slots = find_slots(&matrix);
+        let found: Vec<(Point, Direction, usize)> =
+            slots.iter().map(|slot| (slot.start, slot.direction, slot.cells.len())).collect();
+
+        // Single open cells such as (1, 0) across and (0, 1) down are not slots
+        assert_eq!(
+            found,
+            vec![
+                ((0, 0), Right, 3),
+                ((1, 2), Right, 2),
+                ((2, 0), Right, 4),
+                ((0, 0), Down, 3),
+                ((0, 2), Down, 3),
+                ((1, 3), Down, 2),
+            ]
+        );
+        assert_eq!(slots[0].crossings, vec![(3, 0, 0), (4, 2, 0)]);
+    }
+
+    #[test]
+    fn test_fill_without_repeats() {
+        // The only square of these words reads the same across and down
+        let filler = crossword_filler(&["bat", "are", "ten"]);
+        let matrix = Matrix::with_blocks(&["...", "...", "..."]).unwrap();
+        assert!(filler.fill(&matrix).unwrap().is_none());
+
+        let filler = crossword_filler(&["bat", "are", "ten", "ago", "den", "bad", "age", "ton"]);
+        let fill = filler.fill(&matrix).unwrap().unwrap();
+        assert_eq!(fill.rows, vec!["bad", "age", "ton"]);
+        let words: HashSet<&str> = fill.entries.iter().map(|entry| entry.word.as_str()).collect();
+        assert_eq!(words, HashSet::from(["bad", "age", "ton", "bat", "ago", "den"]));
+    }
+
+    #[test]
+    fn test_fill_with_blocks_and_letters() {
+        let matrix = Matrix::with_blocks(&["C.T", ".#.", "..."]).unwrap();
+        let filler = crossword_filler(&["cat", "cow", "tow"]);
+        assert!(filler.fill(&matrix).unwrap().is_none());
+
+        // "cab" and "ant" fit no crossing word and "tow" would leave no bottom row
+        let filler = crossword_filler(&["cat", "cab", "ant", "cow", "toe", "tow", "woe"]);
+        let fill = filler.fill(&matrix).unwrap().unwrap();
+        assert_eq!(fill.rows, vec!["cat", "o#o", "woe"]);
+        assert_eq!(
+            fill.entries,
+            vec![
+                CrosswordEntry { start: (0, 0), direction: Right, word: "cat".to_string() },
+                CrosswordEntry { start: (2, 0), direction: Right, word: "woe".to_string() },
+                CrosswordEntry { start: (0, 0), direction: Down, word: "cow".to_string() },
+                CrosswordEntry { start: (0, 2), direction: Down, word: "toe".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fill_errors() {
+        let filler = crossword_filler(&["cat"]);
+
+        let matrix = Matrix::with_blocks(&["c4t"]).unwrap();
+        assert!(matches!(filler.fill(&matrix), Err(Error::NotInAlphabet('4'))));
+        let matrix = Matrix::from_tiles(&[&["Qu", "a"]]).unwrap();
+        assert!(matches!(filler.fill(&matrix), Err(Error::InvalidPuzzle(_))));
+    }
+}
@@ -0,0 +1,381 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+
+/// An encoded puzzle: words of symbols that each stand for a different
+/// letter. Cipher letters are stored as their code points, so `'X'` and
+/// `u32::from('X')` are the same symbol.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cryptogram {
+    words: Vec<Vec<u32>>,
+    known: Vec<(u32, char)>,
+}
+
+/// The letter each symbol stands for and the words they decode to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CryptogramSolution {
+    pub words: Vec<String>,
+    pub key: BTreeMap<u32, char>,
+}
+
+impl Cryptogram {
+    /// Reads a substitution cipher such as "GSV JFRXP YILDM ULC". Words are
+    /// separated by whitespace, upper and lower case are the same symbol and
+    /// punctuation is ignored.
+    pub fn from_text(text: &str) -> Cryptogram {
+        let words = text
+            .split_whitespace()
+            .map(|word| {
+                word.chars()
+                    .filter(|symbol| symbol.is_alphanumeric())
+                    .flat_map(char::to_uppercase)
+                    .map(u32::from)
+                    .collect::<Vec<u32>>()
+            })
+            .filter(|word| !word.is_empty())
+            .collect();
+        Cryptogram { words, known: Vec::new() }
+    }
+
+    /// Reads a codeword puzzle, where each number stands for a letter.
+    pub fn from_numbers(words: &[&[u32]]) -> Cryptogram {
+        Cryptogram {
+            words: words.iter().map(|word| word.to_vec()).collect(),
+            known: Vec::new(),
+        }
+    }
+
+    /// Gives away the letter of a symbol, as codeword puzzles usually do for
+    /// a few numbers. Cipher letters are given in upper case, as read by
+    /// [`Cryptogram::from_text`].
+    pub fn with_known(mut self, symbol: impl Into<u32>, letter: char) -> Cryptogram {
+        self.known.push((symbol.into(), letter));
+        self
+    }
+}
+
+/// The pattern of repeated letters in a word, with each new letter named by
+/// the next capital: "letter" is "ABCCBD".
+pub fn pattern_signature(word: &str) -> String {
+    signature(&word.chars().collect::<Vec<char>>())
+        .into_iter()
+        .map(|index| char::from(b'A' + (index % 26) as u8))
+        .collect()
+}
+
+fn signature<S: PartialEq>(symbols: &[S]) -> Vec<usize> {
+    let mut seen: Vec<&S> = Vec::new();
+    symbols
+        .iter()
+        .map(|symbol| match seen.iter().position(|&other| other == symbol) {
+            Some(index) => index,
+            None => {
+                seen.push(symbol);
+                seen.len() - 1
+            }
+        })
+        .collect()
+}
+
+struct CryptogramState {
+    /// Each word with its symbols numbered from 0.
+    words: Vec<Vec<usize>>,
+    /// The dictionary words sharing each word's signature.
+    candidates: Vec<Vec<Vec<char>>>,
+    key: Vec<Option<char>>,
+    used: HashSet<char>,
+    solved: Vec<bool>,
+    max_solutions: usize,
+    solutions: Vec<Vec<Option<char>>>,
+}
+
+impl CryptogramState {
+    fn is_consistent(&self, word: usize, candidate: &[char]) -> bool {
+        self.words[word].iter().zip(candidate).all(|(&symbol, &letter)| match self.key[symbol] {
+            Some(assigned) => assigned == letter,
+            None => !self.used.contains(&letter),
+        })
+    }
+
+    /// Narrows the candidates left for each unsolved word to those still
+    /// consistent with the key. Keys only grow deeper in the search, so each
+    /// level filters the lists of the level above.
+    fn narrow(&self, remaining: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        remaining
+            .iter()
+            .enumerate()
+            .map(|(word, candidates)| {
+                if self.solved[word] {
+                    return Vec::new();
+                }
+                candidates
+                    .iter()
+                    .copied()
+                    .filter(|&candidate| self.is_consistent(word, &self.candidates[word][candidate]))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The unsolved word with the fewest candidates left, preferring longer
+    /// words, along with that number.
+    fn most_constrained(&self, remaining: &[Vec<usize>]) -> Option<(usize, usize)> {
+        (0..self.words.len())
+            .filter(|&word| !self.solved[word])
+            .map(|word| (word, remaining[word].len()))
+            .min_by_key(|&(word, count)| (count, std::cmp::Reverse(self.words[word].len())))
+    }
+}
+
+/// Solves cryptograms by filling in one word at a time, always the one with
+/// the fewest dictionary words left matching its pattern, and walking the
+/// trie to assign letters to its unknown symbols.
+pub(crate) struct CryptogramSolver<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> CryptogramSolver<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> CryptogramSolver<T> {
+        CryptogramSolver { dictionary }
+    }
+
+    /// Returns up to `max_solutions` keys under which every word decodes to a
+    /// dictionary word.
+    pub(crate) fn solve(&self, cryptogram: &Cryptogram, max_solutions: usize) -> Result<Vec<CryptogramSolution>, Error> {
+        let mut symbols: Vec<u32> = Vec::new();
+        let mut symbol_index = |symbol: u32| match symbols.iter().position(|&other| other == symbol) {
+            Some(index) => index,
+            None => {
+                symbols.push(symbol);
+                symbols.len() - 1
+            }
+        };
+        let words: Vec<Vec<usize>> = cryptogram
+            .words
+            .iter()
+            .map(|word| word.iter().map(|&symbol| symbol_index(symbol)).collect())
+            .collect();
+        let known: Vec<(usize, char)> = cryptogram
+            .known
+            .iter()
+            .map(|&(symbol, letter)| (symbol_index(symbol), letter))
+            .collect();
+
+        let mut state = CryptogramState {
+            candidates: self.candidates(&words),
+            key: vec![None; symbols.len()],
+            used: HashSet::new(),
+            solved: vec![false; words.len()],
+            max_solutions,
+            solutions: Vec::new(),
+            words,
+        };
+        let alphabet = self.dictionary.alphabet();
+        for (symbol, letter) in known {
+            let letter = alphabet.normalize(letter).ok_or(Error::NotInAlphabet(letter))?;
+            match state.key[symbol] {
+                // The same hint given twice
+                Some(assigned) if assigned == letter => {}
+                Some(assigned) => {
+                    return Err(Error::InvalidPuzzle(format!("a symbol is given as both {} and {}", assigned, letter)));
+                }
+                None if !state.used.insert(letter) => {
+                    return Err(Error::InvalidPuzzle(format!("{} is given for more than one symbol", letter)));
+                }
+                None => state.key[symbol] = Some(letter),
+            }
+        }
+
+        let remaining: Vec<Vec<usize>> = state.candidates.iter().map(|words| (0..words.len()).collect()).collect();
+        self.fill_next_word(&remaining, &mut state);
+        Ok(state
+            .solutions
+            .iter()
+            .map(|key| CryptogramSolution {
+                words: state
+                    .words
+                    .iter()
+                    .map(|word| word.iter().map(|&symbol| key[symbol].unwrap()).collect())
+                    .collect(),
+                key: symbols.iter().zip(key).map(|(&symbol, letter)| (symbol, letter.unwrap())).collect(),
+            })
+            .collect())
+    }
+
+    /// Indexes the dictionary words of the lengths in the puzzle by their
+    /// signature, keeping those matching each word's.
+    fn candidates(&self, words: &[Vec<usize>]) -> Vec<Vec<Vec<char>>> {
+        let signatures: HashSet<Vec<usize>> = words.iter().map(|word| signature(word)).collect();
+        let max_length = words.iter().map(Vec::len).max().unwrap_or(0);
+        let mut index: HashMap<Vec<usize>, Vec<Vec<char>>> = HashMap::new();
+        self.index_words(&self.dictionary.root(), &mut Vec::new(), max_length, &signatures, &mut index);
+
+        words
+            .iter()
+            .map(|word| index.get(&signature(word)).cloned().unwrap_or_default())
+            .collect()
+    }
+
+    fn index_words<'a>(
+        &'a self,
+        node: &T::Node<'a>,
+        current_word: &mut Vec<char>,
+        max_length: usize,
+        signatures: &HashSet<Vec<usize>>,
+        index: &mut HashMap<Vec<usize>, Vec<Vec<char>>>,
+    ) {
+        if self.dictionary.is_word(node) {
+            let word_signature = signature(current_word);
+            if signatures.contains(&word_signature) {
+                index.entry(word_signature).or_default().push(current_word.clone());
+            }
+        }
+        if current_word.len() == max_length {
+            return;
+        }
+        for &letter in self.dictionary.alphabet().letters() {
+            if let Some(next) = self.dictionary.next(node, letter) {
+                current_word.push(letter);
+                self.index_words(&next, current_word, max_length, signatures, index);
+                current_word.pop();
+            }
+        }
+    }
+
+    fn fill_next_word(&self, remaining: &[Vec<usize>], state: &mut CryptogramState) {
+        if state.solutions.len() >= state.max_solutions {
+            return;
+        }
+        let remaining = state.narrow(remaining);
+        let Some((word, count)) = state.most_constrained(&remaining) else {
+            state.solutions.push(state.key.clone());
+            return;
+        };
+        if count == 0 {
+            return;
+        }
+
+        state.solved[word] = true;
+        self.fill_symbol(&self.dictionary.root(), word, 0, &remaining, state);
+        state.solved[word] = false;
+    }
+
+    /// Decodes symbol `position` of `word`, following its letter through the
+    /// trie if it is known and trying every unused letter otherwise.
+    fn fill_symbol<'a>(
+        &'a self,
+        node: &T::Node<'a>,
+        word: usize,
+        position: usize,
+        remaining: &[Vec<usize>],
+        state: &mut CryptogramState,
+    ) {
+        let Some(&symbol) = state.words[word].get(position) else {
+            if self.dictionary.is_word(node) {
+                self.fill_next_word(remaining, state);
+            }
+            return;
+        };
+
+        if let Some(letter) = state.key[symbol] {
+            if let Some(next) = self.dictionary.next(node, letter) {
+                self.fill_symbol(&next, word, position + 1, remaining, state);
+            }
+            return;
+        }
+        for &letter in self.dictionary.alphabet().letters() {
+            if state.used.contains(&letter) {
+                continue;
+            }
+            let Some(next) = self.dictionary.next(node, letter) else {
+                continue;
+            };
+            state.key[symbol] = Some(letter);
+            state.used.insert(letter);
+            self.fill_symbol(&next, word, position + 1, remaining, state);
+            state.used.remove(&letter);
+            state.key[symbol] = None;
+            if state.solutions.len() >= state.max_solutions {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+
+    fn cryptogram_solver(words: &[&str]) -> CryptogramSolver<HashSet<String>> {
+        CryptogramSolver::new(test_dictionary(words))
+    }
+
+    #[test]
+    fn test_pattern_signature() {
+        assert_eq!(pattern_signature("letter"), "ABCCBD");
+        assert_eq!(pattern_signature("that"), "ABCA");
+        assert_eq!(pattern_signature(""), "");
+    }
+
+    #[test]
+    fn test_from_text() {
+        let cryptogram = Cryptogram::from_text("Gsv, JFRXP!  ulc");
+        let word = |text: &str| text.chars().map(u32::from).collect::<Vec<u32>>();
+
+        assert_eq!(cryptogram.words, vec![word("GSV"), word("JFRXP"), word("ULC")]);
+        let cryptogram = cryptogram.with_known('G', 't');
+        assert_eq!(cryptogram.known, vec![(u32::from('G'), 't')]);
+    }
+
+    #[test]
+    fn test_solve_substitution_cipher() {
+        let solver = cryptogram_solver(&["the", "quick", "quill", "brown", "fox", "box", "bow", "row", "tow"]);
+        let solutions = solver.solve(&Cryptogram::from_text("GSV JFRXP YILDM ULC"), 10).unwrap();
+
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(solutions[0].words, vec!["the", "quick", "brown", "fox"]);
+        assert_eq!(solutions[0].key[&u32::from('G')], 't');
+        assert_eq!(solutions[0].key.len(), 15);
+    }
+
+    #[test]
+    fn test_solve_codeword() {
+        let solver = cryptogram_solver(&["tot", "pop", "tap", "pat", "cat", "act", "apt"]);
+        let cryptogram = Cryptogram::from_numbers(&[&[1, 2, 3], &[3, 2, 1]]);
+
+        let solutions = solver.solve(&cryptogram, 10).unwrap();
+        let words: HashSet<Vec<String>> = solutions.into_iter().map(|solution| solution.words).collect();
+        assert_eq!(
+            words,
+            HashSet::from([
+                vec!["pat".to_string(), "tap".to_string()],
+                vec!["tap".to_string(), "pat".to_string()],
+            ])
+        );
+
+        let solutions = solver.solve(&cryptogram.clone().with_known(1u32, 't'), 10).unwrap();
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(solutions[0].words, vec!["tap", "pat"]);
+        assert_eq!(solver.solve(&cryptogram, 1).unwrap().len(), 1);
+
+        // Repeating a hint changes nothing
+        let repeated = cryptogram.clone().with_known(1u32, 't').with_known(1u32, 'T');
+        assert_eq!(solver.solve(&repeated, 10).unwrap(), solutions);
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let solver = cryptogram_solver(&["tap", "pat"]);
+        let cryptogram = Cryptogram::from_numbers(&[&[1, 2, 3]]);
+
+        let conflicting = cryptogram.clone().with_known(1u32, 't').with_known(2u32, 't');
+        assert!(matches!(solver.solve(&conflicting, 1), Err(Error::InvalidPuzzle(_))));
+        let conflicting = cryptogram.clone().with_known(1u32, 't').with_known(1u32, 'p');
+        assert!(matches!(solver.solve(&conflicting, 1), Err(Error::InvalidPuzzle(_))));
+        assert!(matches!(solver.solve(&cryptogram.with_known(1u32, '4'), 1), Err(Error::NotInAlphabet('4'))));
+        let unsolvable = Cryptogram::from_numbers(&[&[1, 1, 2]]);
+        assert!(solver.solve(&unsolvable, 1).unwrap().is_empty());
+    }
+}
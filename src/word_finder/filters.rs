@@ -0,0 +1,336 @@
+//! Filters that nest into larger ones. "5 to 7 letters, ending in 'ing' or
+//! 'ed', without a 'z'" is written as
+//! `Length::between(5, 7).and(EndsWith::new("ing").or(EndsWith::new("ed"))).and(ExcludesLetters::new("z"))`.
+//! Filters put together at runtime can be boxed as `Box<dyn WordFilters>`,
+//! which is itself a filter.
+//!
+//! `new` only lower cases the letters of a filter. On dictionaries that fold
+//! accents or use another alphabet, `with_alphabet` stores them the way the
+//! dictionary does, so that `StartsWith::with_alphabet("café", &alphabet)`
+//! matches the stored "cafe".
+
+use crate::dictionaries::alphabet::Alphabet;
+use crate::word_finder::WordFilters;
+
+/// Stores `letters` the way a dictionary using `alphabet` does, leaving
+/// letters outside the alphabet lower cased.
+pub(crate) fn normalize_letters(letters: &str, alphabet: &Alphabet) -> String {
+    letters
+        .chars()
+        .flat_map(|letter| alphabet.normalize(letter).unwrap_or(letter).to_lowercase())
+        .collect()
+}
+
+/// Whether `prefix` and `start` agree on the letters they share.
+pub(crate) fn prefix_matches_start(prefix: &str, start: &str) -> bool {
+    prefix.starts_with(start) || start.starts_with(prefix)
+}
+
+/// Passes words passing both filters.
+#[derive(Clone, Debug)]
+pub struct And<A, B>(pub A, pub B);
+
+/// Passes words passing either filter.
+#[derive(Clone, Debug)]
+pub struct Or<A, B>(pub A, pub B);
+
+/// Passes words the inner filter rejects.
+#[derive(Clone, Debug)]
+pub struct Not<F>(pub F);
+
+/// Passes words whose number of letters is within `min..=max`.
+#[derive(Clone, Debug)]
+pub struct Length {
+    min: usize,
+    max: usize,
+}
+
+/// Passes words with `letter` at `position`, counted from 0.
+#[derive(Clone, Debug)]
+pub struct LetterAt {
+    position: usize,
+    letter: char,
+}
+
+#[derive(Clone, Debug)]
+pub struct StartsWith(String);
+
+#[derive(Clone, Debug)]
+pub struct EndsWith(String);
+
+#[derive(Clone, Debug)]
+pub struct Contains(String);
+
+/// Passes words using every one of the letters.
+#[derive(Clone, Debug)]
+pub struct IncludesLetters(Vec<char>);
+
+/// Passes words using none of the letters.
+#[derive(Clone, Debug)]
+pub struct ExcludesLetters(Vec<char>);
+
+impl<A: WordFilters, B: WordFilters> WordFilters for And<A, B> {
+    fn passes_filters(&self, word: &str) -> bool {
+        self.0.passes_filters(word) && self.1.passes_filters(word)
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        self.0.may_match_prefix(prefix) && self.1.may_match_prefix(prefix)
+    }
+}
+
+impl<A: WordFilters, B: WordFilters> WordFilters for Or<A, B> {
+    fn passes_filters(&self, word: &str) -> bool {
+        self.0.passes_filters(word) || self.1.passes_filters(word)
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        self.0.may_match_prefix(prefix) || self.1.may_match_prefix(prefix)
+    }
+}
+
+impl<F: WordFilters> WordFilters for Not<F> {
+    fn passes_filters(&self, word: &str) -> bool {
+        !self.0.passes_filters(word)
+    }
+}
+
+impl Length {
+    pub fn between(min: usize, max: usize) -> Length {
+        Length { min, max }
+    }
+
+    pub fn exactly(length: usize) -> Length {
+        Length::between(length, length)
+    }
+
+    pub fn at_least(min: usize) -> Length {
+        Length::between(min, usize::MAX)
+    }
+
+    pub fn at_most(max: usize) -> Length {
+        Length::between(0, max)
+    }
+}
+
+impl WordFilters for Length {
+    fn passes_filters(&self, word: &str) -> bool {
+        (self.min..=self.max).contains(&word.chars().count())
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        prefix.chars().count() <= self.max
+    }
+}
+
+impl LetterAt {
+    pub fn new(position: usize, letter: char) -> LetterAt {
+        LetterAt {
+            position,
+            letter: letter.to_lowercase().next().unwrap_or(letter),
+        }
+    }
+
+    pub fn with_alphabet(position: usize, letter: char, alphabet: &Alphabet) -> LetterAt {
+        LetterAt {
+            position,
+            letter: alphabet.normalize(letter).unwrap_or(letter),
+        }
+    }
+}
+
+impl WordFilters for LetterAt {
+    fn passes_filters(&self, word: &str) -> bool {
+        word.chars().nth(self.position) == Some(self.letter)
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        prefix.chars().nth(self.position).is_none_or(|letter| letter == self.letter)
+    }
+}
+
+impl StartsWith {
+    pub fn new(prefix: &str) -> StartsWith {
+        StartsWith(prefix.to_lowercase())
+    }
+
+    pub fn with_alphabet(prefix: &str, alphabet: &Alphabet) -> StartsWith {
+        StartsWith(normalize_letters(prefix, alphabet))
+    }
+}
+
+impl WordFilters for StartsWith {
+    fn passes_filters(&self, word: &str) -> bool {
+        word.starts_with(self.0.as_str())
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        prefix_matches_start(prefix, &self.0)
+    }
+}
+
+impl EndsWith {
+    pub fn new(suffix: &str) -> EndsWith {
+        EndsWith(suffix.to_lowercase())
+    }
+
+    pub fn with_alphabet(suffix: &str, alphabet: &Alphabet) -> EndsWith {
+        EndsWith(normalize_letters(suffix, alphabet))
+    }
+}
+
+impl WordFilters for EndsWith {
+    fn passes_filters(&self, word: &str) -> bool {
+        word.ends_with(self.0.as_str())
+    }
+}
+
+impl Contains {
+    pub fn new(infix: &str) -> Contains {
+        Contains(infix.to_lowercase())
+    }
+
+    pub fn with_alphabet(infix: &str, alphabet: &Alphabet) -> Contains {
+        Contains(normalize_letters(infix, alphabet))
+    }
+}
+
+impl WordFilters for Contains {
+    fn passes_filters(&self, word: &str) -> bool {
+        word.contains(self.0.as_str())
+    }
+}
+
+impl IncludesLetters {
+    pub fn new(letters: &str) -> IncludesLetters {
+        IncludesLetters(letters.to_lowercase().chars().collect())
+    }
+
+    pub fn with_alphabet(letters: &str, alphabet: &Alphabet) -> IncludesLetters {
+        IncludesLetters(normalize_letters(letters, alphabet).chars().collect())
+    }
+}
+
+impl WordFilters for IncludesLetters {
+    fn passes_filters(&self, word: &str) -> bool {
+        self.0.iter().all(|&letter| word.contains(letter))
+    }
+}
+
+impl ExcludesLetters {
+    pub fn new(letters: &str) -> ExcludesLetters {
+        ExcludesLetters(letters.to_lowercase().chars().collect())
+    }
+
+    pub fn with_alphabet(letters: &str, alphabet: &Alphabet) -> ExcludesLetters {
+        ExcludesLetters(normalize_letters(letters, alphabet).chars().collect())
+    }
+}
+
+impl WordFilters for ExcludesLetters {
+    fn passes_filters(&self, word: &str) -> bool {
+        !word.chars().any(|letter| self.0.contains(&letter))
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        self.passes_filters(prefix)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::BasicWordFilters;
+
+    #[test]
+    fn test_leaf_filters() {
+        assert!(Length::between(2, 3).passes_filters("ab"));
+        assert!(!Length::between(2, 3).passes_filters("abcd"));
+        assert!(Length::exactly(4).passes_filters("café"));
+        assert!(Length::at_least(4).passes_filters("abcde"));
+        assert!(!Length::at_most(4).passes_filters("abcde"));
+
+        assert!(LetterAt::new(1, 'A').passes_filters("cat"));
+        assert!(!LetterAt::new(1, 'a').passes_filters("act"));
+        assert!(!LetterAt::new(5, 'a').passes_filters("cat"));
+
+        assert!(IncludesLetters::new("tc").passes_filters("act"));
+        assert!(!IncludesLetters::new("tcs").passes_filters("act"));
+        assert!(ExcludesLetters::new("XZ").passes_filters("act"));
+        assert!(!ExcludesLetters::new("z").passes_filters("zap"));
+
+        assert!(StartsWith::new("Re").passes_filters("react"));
+        assert!(EndsWith::new("ct").passes_filters("react"));
+        assert!(Contains::new("ea").passes_filters("react"));
+    }
+
+    #[test]
+    fn test_filters_with_alphabet() {
+        let french = Alphabet::french().with_accent_folding();
+        assert!(StartsWith::with_alphabet("CAFÉ", &french).passes_filters("cafe"));
+        assert!(!StartsWith::new("café").passes_filters("cafe"));
+        assert!(EndsWith::with_alphabet("fé", &french).passes_filters("cafe"));
+        assert!(Contains::with_alphabet("Afé", &french).passes_filters("cafe"));
+        assert!(LetterAt::with_alphabet(3, 'É', &french).passes_filters("cafe"));
+        assert!(IncludesLetters::with_alphabet("éà", &french).passes_filters("cafe"));
+        assert!(!ExcludesLetters::with_alphabet("è", &french).passes_filters("cafe"));
+
+        let greek = Alphabet::greek();
+        assert!(EndsWith::with_alphabet("ος", &greek).passes_filters("λόγοσ"));
+        assert!(LetterAt::with_alphabet(4, 'ς', &greek).passes_filters("λόγοσ"));
+    }
+
+    #[test]
+    fn test_nested_filters() {
+        let filters = Length::between(5, 7)
+            .and(EndsWith::new("ing").or(EndsWith::new("ed")))
+            .and(ExcludesLetters::new("z"));
+
+        assert!(filters.passes_filters("jumped"));
+        assert!(filters.passes_filters("singing"));
+        assert!(!filters.passes_filters("sing"));
+        assert!(!filters.passes_filters("zipped"));
+        assert!(!filters.passes_filters("swimming"));
+
+        let filters = Not(Contains::new("e")).or(LetterAt::new(0, 'e'));
+        assert!(filters.passes_filters("cat"));
+        assert!(filters.passes_filters("eat"));
+        assert!(!filters.passes_filters("tea"));
+    }
+
+    #[test]
+    fn test_filters_may_match_prefix() {
+        assert!(StartsWith::new("qu").may_match_prefix("q"));
+        assert!(StartsWith::new("qu").may_match_prefix("quiz"));
+        assert!(!StartsWith::new("qu").may_match_prefix("qa"));
+        assert!(!Length::at_most(3).may_match_prefix("abcd"));
+        assert!(Length::at_least(3).may_match_prefix("a"));
+        assert!(LetterAt::new(2, 'e').may_match_prefix("th"));
+        assert!(!LetterAt::new(2, 'e').may_match_prefix("tha"));
+        assert!(!ExcludesLetters::new("z").may_match_prefix("piz"));
+        assert!(EndsWith::new("ed").may_match_prefix("x"));
+        assert!(Contains::new("at").may_match_prefix("x"));
+        assert!(IncludesLetters::new("q").may_match_prefix("x"));
+
+        let filters = StartsWith::new("qu").or(StartsWith::new("x")).and(Length::at_most(4));
+        assert!(filters.may_match_prefix("x"));
+        assert!(!filters.may_match_prefix("a"));
+        assert!(!filters.may_match_prefix("quick"));
+        // Negations never prune, as the inner filter only rules prefixes out
+        assert!(Not(StartsWith::new("a")).may_match_prefix("a"));
+    }
+
+    #[test]
+    fn test_boxed_filters() {
+        let mut filters: Vec<Box<dyn WordFilters>> = vec![Box::new(StartsWith::new("s"))];
+        filters.push(Box::new(BasicWordFilters::default().max_length(4)));
+        let filters = filters
+            .into_iter()
+            .reduce(|all, filter| Box::new(And(all, filter)))
+            .unwrap();
+
+        assert!(filters.passes_filters("sing"));
+        assert!(!filters.passes_filters("singer"));
+        assert!(!filters.passes_filters("ring"));
+    }
+}
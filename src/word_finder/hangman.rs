@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+
+/// A letter worth guessing next, with the number of candidates containing it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LetterSuggestion {
+    pub letter: char,
+    pub words: usize,
+    /// The share of the candidates containing the letter, the chance that
+    /// guessing it reveals something.
+    pub probability: f64,
+}
+
+/// The words still possible in a game of Hangman, and the letters not guessed
+/// yet ranked by how likely they are to be in the answer.
+#[derive(Clone, Debug, Default)]
+pub struct HangmanResult {
+    pub candidates: Vec<String>,
+    pub suggestions: Vec<LetterSuggestion>,
+}
+
+struct HangmanState {
+    /// The revealed letter at each position, if any.
+    mask: Vec<Option<char>>,
+    /// Letters that cannot be at a hidden position: the wrong guesses, and
+    /// the revealed letters since every occurrence of a letter is revealed.
+    excluded: Vec<char>,
+    current_word: String,
+    candidates: Vec<String>,
+}
+
+/// Finds the words matching a Hangman mask by following only the trie
+/// branches the revealed letters and the guesses so far allow.
+pub(crate) struct HangmanSolver<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> HangmanSolver<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> HangmanSolver<T> {
+        HangmanSolver { dictionary }
+    }
+
+    /// Solves a mask such as "_a__e_", where `_`, `.` or `?` is a hidden
+    /// letter, given the letters guessed wrong so far. Candidates are in
+    /// alphabetical order, suggestions most likely first.
+    pub(crate) fn solve(&self, mask: &str, wrong_guesses: &str) -> Result<HangmanResult, Error> {
+        let mut state = self.read_game(mask, wrong_guesses)?;
+        self.walk(&self.dictionary.root(), &mut state);
+
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for word in &state.candidates {
+            let mut letters: Vec<char> = word.chars().filter(|letter| !state.excluded.contains(letter)).collect();
+            letters.sort_unstable();
+            letters.dedup();
+            for letter in letters {
+                *counts.entry(letter).or_default() += 1;
+            }
+        }
+        let mut suggestions: Vec<LetterSuggestion> = counts
+            .into_iter()
+            .map(|(letter, words)| LetterSuggestion {
+                letter,
+                words,
+                probability: words as f64 / state.candidates.len() as f64,
+            })
+            .collect();
+        suggestions.sort_by(|a, b| b.words.cmp(&a.words).then(a.letter.cmp(&b.letter)));
+
+        Ok(HangmanResult {
+            candidates: state.candidates,
+            suggestions,
+        })
+    }
+
+    fn read_game(&self, mask: &str, wrong_guesses: &str) -> Result<HangmanState, Error> {
+        let alphabet = self.dictionary.alphabet();
+        let normalize = |letter: char| alphabet.normalize(letter).ok_or(Error::NotInAlphabet(letter));
+        let mask = mask
+            .chars()
+            .map(|letter| match letter {
+                '_' | '.' | '?' => Ok(None),
+                letter => normalize(letter).map(Some),
+            })
+            .collect::<Result<Vec<Option<char>>, Error>>()?;
+
+        let mut excluded: Vec<char> = mask.iter().flatten().copied().collect();
+        for letter in wrong_guesses.chars() {
+            let letter = normalize(letter)?;
+            if mask.contains(&Some(letter)) {
+                return Err(Error::InvalidPuzzle(format!("{} is revealed but was guessed wrong", letter)));
+            }
+            excluded.push(letter);
+        }
+        Ok(HangmanState {
+            mask,
+            excluded,
+            current_word: String::new(),
+            candidates: Vec::new(),
+        })
+    }
+
+    fn walk<'a>(&'a self, node: &T::Node<'a>, state: &mut HangmanState) {
+        let position = state.current_word.chars().count();
+        let Some(&revealed) = state.mask.get(position) else {
+            if self.dictionary.is_word(node) {
+                state.candidates.push(state.current_word.clone());
+            }
+            return;
+        };
+
+        for &letter in self.dictionary.alphabet().letters() {
+            let allowed = match revealed {
+                Some(revealed) => letter == revealed,
+                None => !state.excluded.contains(&letter),
+            };
+            if !allowed {
+                continue;
+            }
+            if let Some(next) = self.dictionary.next(node, letter) {
+                state.current_word.push(letter);
+                self.walk(&next, state);
+                state.current_word.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+    use std::collections::HashSet;
+    use crate::dictionaries::alphabet::Alphabet;
+    use crate::dictionaries::tries::dawg::Dawg;
+    use crate::dictionaries::tries::Trie;
+
+    fn hangman_solver(words: &[&str]) -> HangmanSolver<HashSet<String>> {
+        HangmanSolver::new(test_dictionary(words))
+    }
+
+    #[test]
+    fn test_hangman_candidates() {
+        let solver = hangman_solver(&["banner", "banter", "barter", "canter", "tanner", "carted", "manner", "bane"]);
+        let result = solver.solve("_a__e_", "").unwrap();
+        assert_eq!(result.candidates, vec!["banner", "banter", "barter", "canter", "carted", "manner", "tanner"]);
+
+        // Revealed letters are not hidden elsewhere, so "barter" would have shown
+        // its second R as soon as the first was guessed
+        let result = solver.solve("_a__er", "").unwrap();
+        assert_eq!(result.candidates, vec!["banner", "banter", "canter", "manner", "tanner"]);
+        let result = solver.solve("_A__ER", "bm").unwrap();
+        assert_eq!(result.candidates, vec!["canter", "tanner"]);
+    }
+
+    #[test]
+    fn test_hangman_suggestions() {
+        let solver = hangman_solver(&["banner", "banter", "canter", "manner", "tanner"]);
+        let result = solver.solve("_a__er", "").unwrap();
+
+        // The revealed A, E and R are never suggested, and ties go alphabetically
+        let ranked: Vec<(char, usize)> =
+            result.suggestions.iter().map(|suggestion| (suggestion.letter, suggestion.words)).collect();
+        assert_eq!(ranked, vec![('n', 5), ('t', 3), ('b', 2), ('c', 1), ('m', 1)]);
+        assert_eq!(result.suggestions[0].probability, 1.0);
+        assert_eq!(result.suggestions[1].probability, 0.6);
+    }
+
+    #[test]
+    fn test_hangman_final_sigma() {
+        let mut dawg = Dawg::with_alphabet(Alphabet::greek());
+        for word in ["λόγος", "λόγου"] {
+            dawg.insert(word).unwrap();
+        }
+        dawg.finish();
+        let solver = HangmanSolver::new(Rc::new(dawg));
+
+        // The capital Σ matches the final sigma the word is written with
+        assert_eq!(solver.solve("ΛΌΓΟΣ", "").unwrap().candidates, vec!["λόγοσ"]);
+        assert_eq!(solver.solve("λόγο_", "σ").unwrap().candidates, vec!["λόγου"]);
+    }
+
+    #[test]
+    fn test_hangman_errors() {
+        let solver = hangman_solver(&["cat"]);
+
+        assert!(matches!(solver.solve("c_t", "tx"), Err(Error::InvalidPuzzle(_))));
+        assert!(matches!(solver.solve("c-t", ""), Err(Error::NotInAlphabet('-'))));
+        assert!(matches!(solver.solve("c_t", "4"), Err(Error::NotInAlphabet('4'))));
+        assert_eq!(solver.solve("c__", "").unwrap().candidates, vec!["cat"]);
+        assert!(solver.solve("c__", "t").unwrap().candidates.is_empty());
+    }
+}
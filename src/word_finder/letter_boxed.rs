@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+
+/// Shortest word accepted by Letter Boxed.
+pub const LETTER_BOXED_MIN_LENGTH: usize = 3;
+
+/// A word that can be traced around the box, with the letters it covers.
+struct BoxWord {
+    word: String,
+    first: usize,
+    last: usize,
+    mask: u32,
+}
+
+/// How far along a chain is: the letter the next word starts with and the
+/// letters covered so far.
+type ChainState = (usize, u32);
+
+struct LetterBoxedState {
+    letters: Vec<char>,
+    sides: Vec<usize>,
+    current_word: String,
+    first: usize,
+    mask: u32,
+    words: Vec<BoxWord>,
+}
+
+/// Solves Letter Boxed: chains of words using every letter on the sides of
+/// a box, where consecutive letters of a word come from different sides and
+/// each word starts with the last letter of the one before.
+pub(crate) struct LetterBoxedSolver<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> LetterBoxedSolver<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> LetterBoxedSolver<T> {
+        LetterBoxedSolver { dictionary }
+    }
+
+    /// Returns every chain with the fewest words, if there is one of at most
+    /// `max_words`, ordered by total number of letters and then alphabetically.
+    pub(crate) fn solve(&self, sides: &[&str], max_words: usize) -> Result<Vec<Vec<String>>, Error> {
+        let mut state = self.read_sides(sides)?;
+        for index in 0..state.letters.len() {
+            state.first = index;
+            self.traverse(&self.dictionary.root(), index, 0, &mut state);
+        }
+        let full_mask = if state.letters.len() == 32 { u32::MAX } else { (1 << state.letters.len()) - 1 };
+
+        let mut chains = shortest_chains(&state.words, state.letters.len(), full_mask, max_words);
+        chains.sort_by_key(|chain| {
+            let letters: usize = chain.iter().map(|word| word.chars().count()).sum();
+            (letters, chain.clone())
+        });
+        Ok(chains)
+    }
+
+    fn read_sides(&self, sides: &[&str]) -> Result<LetterBoxedState, Error> {
+        let alphabet = self.dictionary.alphabet();
+        let mut state = LetterBoxedState {
+            letters: Vec::new(),
+            sides: Vec::new(),
+            current_word: String::new(),
+            first: 0,
+            mask: 0,
+            words: Vec::new(),
+        };
+
+        for (side, letters) in sides.iter().enumerate() {
+            for letter in letters.chars() {
+                let letter = alphabet.normalize(letter).ok_or(Error::NotInAlphabet(letter))?;
+                if state.letters.contains(&letter) {
+                    return Err(Error::InvalidPuzzle(format!("letter {} is on the box twice", letter)));
+                }
+                state.letters.push(letter);
+                state.sides.push(side);
+            }
+        }
+        if state.letters.len() > u32::BITS as usize {
+            return Err(Error::InvalidPuzzle(format!("{} letters is more than 32", state.letters.len())));
+        }
+        Ok(state)
+    }
+
+    /// Extends the word with the letter at `index`, then with every letter
+    /// on another side, for as long as the trie has words down that path.
+    fn traverse<'a>(&'a self, node: &T::Node<'a>, index: usize, length: usize, state: &mut LetterBoxedState) {
+        let Some(next) = self.dictionary.next(node, state.letters[index]) else {
+            return;
+        };
+        let word_length = state.current_word.len();
+        let mask = state.mask;
+        state.current_word.push(state.letters[index]);
+        state.mask |= 1 << index;
+
+        if length + 1 >= LETTER_BOXED_MIN_LENGTH && self.dictionary.is_word(&next) {
+            state.words.push(BoxWord {
+                word: state.current_word.clone(),
+                first: state.first,
+                last: index,
+                mask: state.mask,
+            });
+        }
+        for following in 0..state.letters.len() {
+            if state.sides[following] != state.sides[index] {
+                self.traverse(&next, following, length + 1, state);
+            }
+        }
+
+        state.mask = mask;
+        state.current_word.truncate(word_length);
+    }
+}
+
+/// Breadth first search over chain states, remembering every way each state
+/// is first reached so that all the shortest chains can be read back.
+fn shortest_chains(words: &[BoxWord], letter_count: usize, full_mask: u32, max_words: usize) -> Vec<Vec<String>> {
+    let mut words_by_first = vec![Vec::new(); letter_count];
+    for (index, word) in words.iter().enumerate() {
+        words_by_first[word.first].push(index);
+    }
+
+    let mut depths: HashMap<ChainState, usize> = HashMap::new();
+    let mut predecessors: HashMap<ChainState, Vec<(Option<ChainState>, usize)>> = HashMap::new();
+    let mut frontier: Vec<ChainState> = Vec::new();
+    for (index, word) in words.iter().enumerate() {
+        let state = (word.last, word.mask);
+        if depths.insert(state, 1).is_none() {
+            frontier.push(state);
+        }
+        predecessors.entry(state).or_default().push((None, index));
+    }
+
+    let mut depth = 1;
+    loop {
+        let goals: Vec<ChainState> = frontier.iter().copied().filter(|&(_, mask)| mask == full_mask).collect();
+        if !goals.is_empty() {
+            let mut chains = Vec::new();
+            for goal in goals {
+                read_chains(goal, words, &predecessors, &mut Vec::new(), &mut chains);
+            }
+            return chains;
+        }
+        if depth >= max_words || frontier.is_empty() {
+            return Vec::new();
+        }
+
+        let mut next_frontier = Vec::new();
+        for &(last, mask) in &frontier {
+            for &index in &words_by_first[last] {
+                let word = &words[index];
+                let state = (word.last, mask | word.mask);
+                match depths.get(&state) {
+                    None => {
+                        depths.insert(state, depth + 1);
+                        next_frontier.push(state);
+                    }
+                    Some(&found) if found != depth + 1 => continue,
+                    Some(_) => {}
+                }
+                predecessors.entry(state).or_default().push((Some((last, mask)), index));
+            }
+        }
+        frontier = next_frontier;
+        depth += 1;
+    }
+}
+
+fn read_chains(
+    state: ChainState,
+    words: &[BoxWord],
+    predecessors: &HashMap<ChainState, Vec<(Option<ChainState>, usize)>>,
+    chain: &mut Vec<usize>,
+    chains: &mut Vec<Vec<String>>,
+) {
+    for &(previous, index) in &predecessors[&state] {
+        chain.push(index);
+        match previous {
+            Some(previous) => read_chains(previous, words, predecessors, chain, chains),
+            None => chains.push(chain.iter().rev().map(|&index| words[index].word.clone()).collect()),
+        }
+        chain.pop();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dictionaries::alphabet::Alphabet;
+    use crate::dictionaries::tries::Trie;
+    use crate::dictionaries::tries::dawg::Dawg;
+    use crate::word_finder::test_dictionary;
+    use std::collections::HashSet;
+
+    fn letter_boxed_solver(words: &[&str]) -> LetterBoxedSolver<HashSet<String>> {
+        LetterBoxedSolver::new(test_dictionary(words))
+    }
+
+    #[test]
+    fn test_words_follow_the_sides() {
+        // Sides: abc, def, ghi, jkl
+        let solver = letter_boxed_solver(&["adg", "aag", "abd", "adgj", "dad", "ad"]);
+        let mut state = solver.read_sides(&["ABC", "DEF", "GHI", "JKL"]).unwrap();
+        for index in 0..state.letters.len() {
+            state.first = index;
+            solver.traverse(&solver.dictionary.root(), index, 0, &mut state);
+        }
+        let words: HashSet<&str> = state.words.iter().map(|word| word.word.as_str()).collect();
+
+        // Repeating a side or a letter back to back is not allowed, and "ad" is too short
+        assert_eq!(words, HashSet::from(["adg", "adgj", "dad"]));
+    }
+
+    #[test]
+    fn test_solve_shortest_chains() {
+        let solver = letter_boxed_solver(&[
+            "adgj", "jbehk", "kcfil", "adgjbehkcfil", "jbe", "ehkcfil", "adgjbe",
+        ]);
+        let chains = solver.solve(&["abc", "def", "ghi", "jkl"], 5).unwrap();
+
+        assert_eq!(chains, vec![vec!["adgjbehkcfil".to_string()]]);
+
+        let solver = letter_boxed_solver(&["adgj", "jbehk", "kcfil", "jbe", "ehkcfil", "adgjbe"]);
+        let chains = solver.solve(&["abc", "def", "ghi", "jkl"], 5).unwrap();
+        assert_eq!(chains, vec![vec!["adgjbe".to_string(), "ehkcfil".to_string()]]);
+        assert!(solver.solve(&["abc", "def", "ghi", "jkl"], 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_solve_all_chains_of_the_same_length() {
+        let solver = letter_boxed_solver(&["adgj", "jbehk", "kcfil", "jbehkcfil", "adgjb", "behkcfil"]);
+        let chains = solver.solve(&["abc", "def", "ghi", "jkl"], 5).unwrap();
+
+        assert_eq!(
+            chains,
+            vec![
+                vec!["adgj".to_string(), "jbehkcfil".to_string()],
+                vec!["adgjb".to_string(), "behkcfil".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chains_ordered_by_letters_not_bytes() {
+        let mut dawg = Dawg::with_alphabet(Alphabet::french());
+        for word in ["adgjbé", "adgjbéhkc", "cfil", "éhkcfil"] {
+            dawg.insert(word).unwrap();
+        }
+        dawg.finish();
+        let solver = LetterBoxedSolver::new(Rc::new(dawg));
+        let chains = solver.solve(&["abc", "déf", "ghi", "jkl"], 3).unwrap();
+
+        // Both chains have 13 letters, so they are in alphabetical order even
+        // though the first spells é twice and takes more bytes
+        assert_eq!(
+            chains,
+            vec![
+                vec!["adgjbé".to_string(), "éhkcfil".to_string()],
+                vec!["adgjbéhkc".to_string(), "cfil".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_box() {
+        let solver = letter_boxed_solver(&["adg"]);
+        assert!(matches!(solver.solve(&["abc", "dea"], 3), Err(Error::InvalidPuzzle(_))));
+        assert!(matches!(solver.solve(&["ab1"], 3), Err(Error::NotInAlphabet('1'))));
+    }
+}
@@ -1,6 +1,8 @@
+use crate::error::Error;
 use crate::word_finder::matrix::Direction::{Down, Left, LowerLeft, LowerRight, Right, Up, UpperLeft, UpperRight};
 
-pub(crate) enum Direction {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
     Up,
     UpperLeft,
     UpperRight,
@@ -11,11 +13,26 @@ pub(crate) enum Direction {
     Right,
 }
 
-pub(crate) type Point = (usize, usize);
+impl Direction {
+    pub const ALL: [Direction; 8] = [Up, UpperLeft, UpperRight, Down, LowerLeft, LowerRight, Left, Right];
+}
+
+/// A cell of a matrix as (row, column).
+pub type Point = (usize, usize);
 
+/// A cell of a matrix. Tiles are usually a single letter, but some games
+/// have multi-letter tiles such as the "Qu" Boggle die. Blocked cells, the
+/// black squares of a crossword, have no tile and cannot be moved into.
 #[derive(PartialEq, Debug)]
 struct MatrixElement {
-    letter: char,
+    tile: String,
+    blocked: bool,
+}
+
+impl MatrixElement {
+    fn tile(tile: String) -> MatrixElement {
+        MatrixElement { tile, blocked: false }
+    }
 }
 
 pub(crate) struct Matrix {
@@ -26,40 +43,78 @@ pub(crate) struct Matrix {
 }
 
 impl Matrix {
-    pub(crate) fn new(nested_slice: &[&[char]]) -> Matrix {
+    pub(crate) fn new(nested_slice: &[&[char]]) -> Result<Matrix, Error> {
+        Matrix::from_elements(nested_slice, |letter| MatrixElement::tile(letter.to_string()))
+    }
+
+    pub(crate) fn from_tiles(nested_slice: &[&[&str]]) -> Result<Matrix, Error> {
+        Matrix::from_elements(nested_slice, |tile| MatrixElement::tile(tile.to_string()))
+    }
+
+    /// Reads a crossword grid, one string per row: `#` is a blocked cell,
+    /// `.` or `?` an empty one and anything else a pre-filled letter.
+    pub(crate) fn with_blocks(rows: &[&str]) -> Result<Matrix, Error> {
+        let rows: Vec<Vec<char>> = rows.iter().map(|row| row.chars().collect()).collect();
+        let rows: Vec<&[char]> = rows.iter().map(Vec::as_slice).collect();
+        Matrix::from_elements(&rows, |&cell| match cell {
+            '#' => MatrixElement {
+                tile: String::new(),
+                blocked: true,
+            },
+            '.' | '?' => MatrixElement::tile(String::new()),
+            letter => MatrixElement::tile(letter.to_string()),
+        })
+    }
+
+    fn from_elements<T>(nested_slice: &[&[T]], to_element: impl Fn(&T) -> MatrixElement) -> Result<Matrix, Error> {
         let mut elements = Vec::new();
-        let width = nested_slice.get(0).unwrap().len();
+        let width = nested_slice.first().map_or(0, |row| row.len());
         let height = nested_slice.len();
+        if width == 0 {
+            return Err(Error::EmptyGrid);
+        }
 
-        for row in nested_slice.iter() {
-            if row.len() != width as usize {
-                panic!("Rows are of different sizes");
+        for (index, row) in nested_slice.iter().enumerate() {
+            if row.len() != width {
+                return Err(Error::RaggedGrid {
+                    row: index,
+                    expected: width,
+                    found: row.len(),
+                });
             }
 
             for col in row.iter() {
-                elements.push(MatrixElement { letter: *col })
+                elements.push(to_element(col))
             }
         }
 
-        Matrix {
+        Ok(Matrix {
             elements,
             height,
             width,
             capacity: width * height,
-        }
+        })
     }
-    pub(crate) fn get_letter(&self, point: Point) -> char {
-        self.get_element(point).unwrap().letter
+    pub(crate) fn get_tile(&self, point: Point) -> &str {
+        &self.get_element(point).unwrap().tile
     }
 
     pub(crate) fn get_capacity(&self) -> usize {
         self.capacity
     }
 
+    pub(crate) fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub(crate) fn is_blocked(&self, point: Point) -> bool {
+        self.get_element(point).is_some_and(|element| element.blocked)
+    }
+
     fn get_element(&self, point: Point) -> Option<&MatrixElement> {
-        if point.0 < self.width && point.1 < self.height {
+        if point.0 < self.height && point.1 < self.width {
             let index = point.0 * self.width + point.1;
-            self.elements.get(index as usize)
+            self.elements.get(index)
         } else {
             None
         }
@@ -73,12 +128,17 @@ impl Matrix {
         }
     }
 
+    pub(crate) fn get_index_from_point(&self, point: Point) -> usize {
+        if point.0 < self.height && point.1 < self.width {
+            point.0 * self.width + point.1
+        } else {
+            panic!("Point out of bounds");
+        }
+    }
+
     fn validate_point(&self, row: usize, col: usize) -> bool {
         let point = (row, col);
-        match self.get_element(point) {
-            None => false,
-            Some(_) => true,
-        }
+        self.get_element(point).is_some_and(|element| !element.blocked)
     }
 
     pub(crate) fn next(&self, point: Point, direction: &Direction) -> Option<Point> {
@@ -108,31 +168,46 @@ mod test {
 
     #[test]
     fn test_new_matrix() {
-        let matrix = Matrix::new(&[&['c', 'b', 'c'], &['a', 'e', 'e'], &['t', 't', 'l']]);
+        let matrix = Matrix::new(&[&['c', 'b', 'c'], &['a', 'e', 'e'], &['t', 't', 'l']]).unwrap();
+
+        assert_eq!(matrix.elements.first().unwrap().tile, "c");
+        assert_eq!(matrix.elements.get(3).unwrap().tile, "a");
+        assert_eq!(matrix.elements.get(4).unwrap().tile, "e");
+        assert_eq!(matrix.elements.get(8).unwrap().tile, "l");
+    }
+
+    #[test]
+    fn test_new_matrix_from_tiles() {
+        let matrix = Matrix::from_tiles(&[&["Qu", "a"], &["i", "t"]]).unwrap();
+
+        assert_eq!(matrix.get_tile((0, 0)), "Qu");
+        assert_eq!(matrix.get_tile((1, 1)), "t");
+    }
+
+    #[test]
+    fn test_new_matrix_rejects_uneven_matrix() {
+        let result = Matrix::new(&[&['a', 'b'], &['d', 'e', 'f'], &['g', 'h', 'i']]);
 
-        assert_eq!(matrix.elements.get(0).unwrap().letter, 'c');
-        assert_eq!(matrix.elements.get(3).unwrap().letter, 'a');
-        assert_eq!(matrix.elements.get(4).unwrap().letter, 'e');
-        assert_eq!(matrix.elements.get(8).unwrap().letter, 'l');
+        assert!(matches!(result, Err(Error::RaggedGrid { row: 1, expected: 2, found: 3 })));
     }
 
     #[test]
-    #[should_panic(expected = "Rows are of different sizes")]
-    fn test_new_matrix_panics_for_uneven_matrix() {
-        Matrix::new(&[&['a', 'b'], &['d', 'e', 'f'], &['g', 'h', 'i']]);
+    fn test_new_matrix_rejects_empty_matrix() {
+        assert!(matches!(Matrix::new(&[]), Err(Error::EmptyGrid)));
+        assert!(matches!(Matrix::new(&[&[], &[]]), Err(Error::EmptyGrid)));
     }
 
     #[test]
     fn test_get_matrix_element() {
-        let matrix = Matrix::new(&[&['a', 'b', 'c'], &['d', 'e', 'f'], &['g', 'h', 'i']]);
+        let matrix = Matrix::new(&[&['a', 'b', 'c'], &['d', 'e', 'f'], &['g', 'h', 'i']]).unwrap();
 
-        assert_eq!(matrix.get_element((0, 0)).unwrap().letter, 'a');
-        assert_eq!(matrix.get_element((2, 2)).unwrap().letter, 'i');
+        assert_eq!(matrix.get_element((0, 0)).unwrap().tile, "a");
+        assert_eq!(matrix.get_element((2, 2)).unwrap().tile, "i");
     }
 
     #[test]
     fn test_matrix_next() {
-        let matrix = Matrix::new(&[&['a', 'b', 'c'], &['d', 'e', 'f'], &['g', 'h', 'i']]);
+        let matrix = Matrix::new(&[&['a', 'b', 'c'], &['d', 'e', 'f'], &['g', 'h', 'i']]).unwrap();
 
         assert_eq!(matrix.next((0, 0), &Down).unwrap(), (1, 0));
         assert_eq!(matrix.next((0, 1), &Left).unwrap(), (0, 0));
@@ -140,13 +215,28 @@ mod test {
         assert_eq!(matrix.next((1, 1), &Up).unwrap(), (0, 1));
     }
 
+    #[test]
+    fn test_matrix_with_blocks() {
+        let matrix = Matrix::with_blocks(&["c.#", "a?t"]).unwrap();
+
+        assert_eq!(matrix.get_tile((0, 0)), "c");
+        assert_eq!(matrix.get_tile((0, 1)), "");
+        assert!(matrix.is_blocked((0, 2)));
+        assert!(!matrix.is_blocked((1, 2)));
+        assert_eq!(matrix.next((0, 1), &Right), None);
+        assert_eq!(matrix.next((1, 2), &Up), None);
+        assert_eq!(matrix.next((0, 1), &Down), Some((1, 1)));
+        assert!(matches!(Matrix::with_blocks(&["ab", "c"]), Err(Error::RaggedGrid { row: 1, expected: 2, found: 1 })));
+    }
+
     #[test]
     fn test_get_point_from_index() {
-        let matrix = Matrix::new(&[&['a', 'b', 'c'], &['d', 'e', 'f'], &['g', 'h', 'i']]);
+        let matrix = Matrix::new(&[&['a', 'b', 'c'], &['d', 'e', 'f'], &['g', 'h', 'i']]).unwrap();
 
         assert_eq!(matrix.get_point_from_index(5), (1, 2));
         assert_eq!(matrix.get_point_from_index(7), (2, 1));
         assert_eq!(matrix.get_point_from_index(0), (0, 0));
         assert_eq!(matrix.get_point_from_index(8), (2, 2));
+        assert_eq!(matrix.get_index_from_point((1, 2)), 5);
     }
 }
@@ -1,14 +1,19 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use crate::word_finder::{WordFilters, WordFinder};
 use crate::word_finder::matrix::{Direction, Matrix, Point};
 use crate::word_finder::matrix::Direction::{Down, Left, LowerLeft, LowerRight, Right, Up, UpperLeft, UpperRight};
 use crate::dictionaries::Dictionary;
 
+/// Each word found in a matrix along with the cells that spell it, in order.
+pub type WordPaths = HashMap<String, Vec<Vec<Point>>>;
+
 struct WordFinderState {
     current_word: String,
     visited_points: HashSet<Point>,
-    words_found: HashSet<String>,
+    current_path: Vec<Point>,
+    words_found: WordPaths,
+    all_paths: bool,
 }
 
 pub(crate) struct MatrixWordFinder<T> {
@@ -17,11 +22,24 @@ pub(crate) struct MatrixWordFinder<T> {
 }
 
 impl WordFinderState {
-    fn new() -> WordFinderState {
+    fn new(all_paths: bool) -> WordFinderState {
         WordFinderState {
             current_word: String::new(),
             visited_points: HashSet::new(),
-            words_found: HashSet::new(),
+            current_path: Vec::new(),
+            words_found: HashMap::new(),
+            all_paths,
+        }
+    }
+
+    fn record_word(&mut self) {
+        match self.words_found.get_mut(&self.current_word) {
+            Some(paths) if self.all_paths => paths.push(self.current_path.clone()),
+            Some(_) => {}
+            None => {
+                let path = self.current_path.clone();
+                self.words_found.insert(self.current_word.clone(), vec![path]);
+            }
         }
     }
 }
@@ -42,6 +60,18 @@ impl<T: Dictionary> MatrixWordFinder<T> {
         }
     }
 
+    /// Finds the words in `matrix` along with the path of cells spelling
+    /// them: the first path found for each word, or every path if `all_paths`.
+    pub(crate) fn search_paths<F: WordFilters>(&self, matrix: &Matrix, filters: &F, all_paths: bool) -> WordPaths {
+        let mut state = WordFinderState::new(all_paths);
+
+        for index in 0..matrix.get_capacity() {
+            let point = matrix.get_point_from_index(index);
+            self.traverse(point, &mut state, matrix, filters);
+        }
+        state.words_found
+    }
+
     fn traverse<F: WordFilters>(
         &self,
         point: Point,
@@ -49,40 +79,40 @@ impl<T: Dictionary> MatrixWordFinder<T> {
         matrix: &Matrix,
         filters: &F,
     ) {
-        if self.dictionary.starts_with(&state.current_word) {
+        let alphabet = self.dictionary.alphabet();
+        let word_length = state.current_word.len();
+        for letter in matrix.get_tile(point).chars() {
+            state.current_word.push(alphabet.normalize(letter).unwrap_or(letter));
+        }
+
+        if filters.may_match_prefix(&state.current_word) && self.dictionary.starts_with(&state.current_word) {
+            state.visited_points.insert(point);
+            state.current_path.push(point);
+
             if filters.passes_filters(&state.current_word)
                 && self.dictionary.search(&state.current_word)
             {
-                state.words_found.insert(state.current_word.to_string());
+                state.record_word();
             }
 
-            state.visited_points.insert((point.0, point.1));
-            state.current_word.push(matrix.get_letter(point));
-
             for direction in self.directions {
                 if let Some(next_point) = matrix.next(point, direction) {
-                    if state.visited_points.contains(&next_point) {
-                    } else {
+                    if !state.visited_points.contains(&next_point) {
                         self.traverse(next_point, state, matrix, filters);
                     }
                 }
             }
 
-            state.current_word.pop();
-            state.visited_points.remove(&(point.0, point.1));
+            state.current_path.pop();
+            state.visited_points.remove(&point);
         }
+        state.current_word.truncate(word_length);
     }
 }
 
 impl<T: Dictionary> WordFinder<Matrix> for MatrixWordFinder<T> {
     fn search<F: WordFilters>(&self, matrix: &Matrix, filters: &F) -> HashSet<String> {
-        let mut state = WordFinderState::new();
-
-        for index in 0..matrix.get_capacity() {
-            let point = matrix.get_point_from_index(index);
-            self.traverse(point, &mut state, &matrix, filters);
-        }
-        state.words_found
+        self.search_paths(matrix, filters, false).into_keys().collect()
     }
 }
 
@@ -90,7 +120,12 @@ impl<T: Dictionary> WordFinder<Matrix> for MatrixWordFinder<T> {
 mod test {
     use super::*;
     use std::time::Instant;
-    use crate::word_finder::BasicWordFilters;
+    use crate::dictionaries::alphabet::Alphabet;
+    use crate::dictionaries::tries::Trie;
+    use crate::dictionaries::tries::dawg::Dawg;
+    use crate::dictionaries::tries::trie_builder::{TrieBuilder, TxtFileTrieBuilder};
+    use crate::word_finder::{BasicWordFilters, WithoutPruning};
+    use crate::SCRABBLE_DICTIONARY_PATH;
 
     #[test]
     fn test_matrix_word_finder_search() {
@@ -110,7 +145,7 @@ mod test {
             &['r', 'b', 'a', 'o'],
             &['n', 'l', 's', 'r'],
             &['c', 'a', 'n', 'o'],
-        ]);
+        ]).unwrap();
         let matrix_word_finder = MatrixWordFinder::new(Rc::new(dictionary), true);
         let filters: BasicWordFilters = Default::default();
 
@@ -128,4 +163,76 @@ mod test {
         assert!(!words.contains("scan"));
         assert!(!words.contains("poor"));
     }
+
+    #[test]
+    fn test_matrix_word_finder_search_paths() {
+        let dictionary = HashSet::from(["tot".to_string(), "to".to_string(), "ox".to_string()]);
+        let matrix = Matrix::new(&[
+            &['t', 'o', 't'],
+            &['x', 'x', 'x'],
+        ]).unwrap();
+        let matrix_word_finder = MatrixWordFinder::new(Rc::new(dictionary), false);
+        let filters: BasicWordFilters = Default::default();
+
+        let paths = matrix_word_finder.search_paths(&matrix, &filters, true);
+        assert_eq!(paths["tot"], vec![vec![(0, 0), (0, 1), (0, 2)], vec![(0, 2), (0, 1), (0, 0)]]);
+        assert_eq!(paths["to"], vec![vec![(0, 0), (0, 1)], vec![(0, 2), (0, 1)]]);
+        assert_eq!(paths["ox"], vec![vec![(0, 1), (1, 1)]]);
+
+        let paths = matrix_word_finder.search_paths(&matrix, &filters, false);
+        assert_eq!(paths["tot"], vec![vec![(0, 0), (0, 1), (0, 2)]]);
+        assert_eq!(paths.len(), 3);
+    }
+
+    #[test]
+    fn test_matrix_word_finder_search_with_alphabet() {
+        let mut dawg = Dawg::with_alphabet(Alphabet::spanish());
+        for word in ["año", "caña", "niño"] {
+            dawg.insert(word).unwrap();
+        }
+        dawg.finish();
+
+        let matrix = Matrix::new(&[
+            &['C', 'A', 'Ñ'],
+            &['x', 'x', 'O'],
+            &['x', 'x', 'x'],
+        ]).unwrap();
+        let matrix_word_finder = MatrixWordFinder::new(Rc::new(dawg), true);
+        let filters: BasicWordFilters = Default::default();
+        let words = matrix_word_finder.search(&matrix, &filters);
+
+        assert_eq!(words, HashSet::from(["año".to_string()]));
+    }
+
+    #[test]
+    fn test_matrix_prefix_pruning_compared_to_word_filtering() {
+        let mut dawg = Dawg::new();
+        TxtFileTrieBuilder::new(SCRABBLE_DICTIONARY_PATH).unwrap().build(&mut dawg).unwrap();
+        let matrix_word_finder = MatrixWordFinder::new(Rc::new(dawg), true);
+        let matrix = Matrix::new(&[
+            &['s', 't', 'a', 'r', 'e'],
+            &['l', 'i', 'n', 'g', 'o'],
+            &['p', 'e', 'a', 'c', 'h'],
+            &['m', 'o', 'u', 's', 'e'],
+            &['d', 'r', 'i', 'v', 'e'],
+        ]).unwrap();
+        let filters = BasicWordFilters::default().starts_with("st").min_length(4);
+
+        let start_time = Instant::now();
+        let expected = matrix_word_finder.search(&matrix, &WithoutPruning(filters.clone()));
+        let filtering_time = Instant::now() - start_time;
+
+        let start_time = Instant::now();
+        let words = matrix_word_finder.search(&matrix, &filters);
+        let pruning_time = Instant::now() - start_time;
+
+        println!(
+            "Words starting with st: word filtering {:?}, prefix pruning {:?}, {} words",
+            filtering_time,
+            pruning_time,
+            words.len()
+        );
+        assert_eq!(words, expected);
+        assert!(words.contains("stain"));
+    }
 }
@@ -1,39 +1,190 @@
 use std::collections::HashSet;
+#[cfg(test)]
+use std::rc::Rc;
+use crate::dictionaries::alphabet::Alphabet;
+use crate::word_finder::filters::{normalize_letters, prefix_matches_start, And, Or};
 
 pub(crate) mod matrix_word_finder;
 pub(crate) mod matrix;
 pub(crate) mod anagram_word_finder;
+pub(crate) mod phrase_anagram_word_finder;
+pub(crate) mod boggle;
+pub(crate) mod boggle_generator;
+pub(crate) mod word_search;
+pub(crate) mod pattern_word_finder;
+pub(crate) mod regex;
+pub(crate) mod scrabble;
+pub(crate) mod scoring;
+pub(crate) mod wordle;
+pub(crate) mod spelling_bee;
+pub(crate) mod letter_boxed;
+pub(crate) mod word_ladder;
+pub(crate) mod cryptogram;
+pub(crate) mod crossword;
+pub(crate) mod hangman;
+pub mod filters;
 
 pub(crate) trait WordFinder<T> {
     fn search<F: WordFilters>(&self, data: &T, filters: &F) -> HashSet<String>;
 }
 
-pub(crate) trait WordFilters {
+/// Decides which of the words found by a search are kept. Words are passed
+/// as stored in the dictionary, which is lower case for every built-in alphabet.
+pub trait WordFilters {
     fn passes_filters(&self, word: &str) -> bool;
+
+    /// Whether a word starting with `prefix` could still pass. Finders stop
+    /// extending a prefix once this is false, so a filter should only return
+    /// false when no longer word can pass either.
+    fn may_match_prefix(&self, _prefix: &str) -> bool {
+        true
+    }
+
+    fn and<F: WordFilters>(self, other: F) -> And<Self, F> where Self: Sized {
+        And(self, other)
+    }
+
+    fn or<F: WordFilters>(self, other: F) -> Or<Self, F> where Self: Sized {
+        Or(self, other)
+    }
 }
 
-#[derive(Default)]
+impl<F: WordFilters + ?Sized> WordFilters for Box<F> {
+    fn passes_filters(&self, word: &str) -> bool {
+        self.as_ref().passes_filters(word)
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        self.as_ref().may_match_prefix(prefix)
+    }
+}
+
+impl<F: WordFilters + ?Sized> WordFilters for &F {
+    fn passes_filters(&self, word: &str) -> bool {
+        (**self).passes_filters(word)
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        (**self).may_match_prefix(prefix)
+    }
+}
+
+/// A small in-memory dictionary for the finders' unit tests.
+#[cfg(test)]
+pub(crate) fn test_dictionary(words: &[&str]) -> Rc<HashSet<String>> {
+    Rc::new(words.iter().map(|word| word.to_string()).collect())
+}
+
+/// Checks complete words only, the way every filter worked before prefix
+/// pruning, so benchmarks can compare the two.
+#[cfg(test)]
+pub(crate) struct WithoutPruning<F>(pub F);
+
+#[cfg(test)]
+impl<F: WordFilters> WordFilters for WithoutPruning<F> {
+    fn passes_filters(&self, word: &str) -> bool {
+        self.0.passes_filters(word)
+    }
+}
+
+/// Filters on the letters and length of a word, built up one condition at a
+/// time, e.g. `BasicWordFilters::default().starts_with("re").contains("at")`.
+/// Every condition has to hold.
+///
+/// Letters are only lower cased by default. Filters made with
+/// [`BasicWordFilters::for_alphabet`] store them the way the dictionary does,
+/// so that `contains("é")` matches an accent folded "cafe".
+#[derive(Clone, Debug, Default)]
 pub struct BasicWordFilters {
+    alphabet: Option<Alphabet>,
     starts_with: String,
     ends_with: String,
     contains: String,
+    min_length: usize,
+    max_length: Option<usize>,
+    excluded_letters: Vec<char>,
 }
 
 impl BasicWordFilters {
-    fn new(starts_with: &str, ends_with: &str, contains: &str) -> BasicWordFilters {
+    pub fn new(starts_with: &str, ends_with: &str, contains: &str) -> BasicWordFilters {
+        BasicWordFilters::default()
+            .starts_with(starts_with)
+            .ends_with(ends_with)
+            .contains(contains)
+    }
+
+    /// Filters whose letters are normalized with `alphabet`.
+    pub fn for_alphabet(alphabet: &Alphabet) -> BasicWordFilters {
         BasicWordFilters {
-            starts_with: starts_with.to_owned(),
-            ends_with: ends_with.to_owned(),
-            contains: contains.to_owned(),
+            alphabet: Some(alphabet.clone()),
+            ..Default::default()
+        }
+    }
+
+    /// Stores `letters` as the dictionary would, leaving letters outside the
+    /// alphabet lower cased.
+    fn normalize(&self, letters: &str) -> String {
+        match &self.alphabet {
+            Some(alphabet) => normalize_letters(letters, alphabet),
+            None => letters.to_lowercase(),
         }
     }
+
+    pub fn starts_with(mut self, prefix: &str) -> BasicWordFilters {
+        self.starts_with = self.normalize(prefix);
+        self
+    }
+
+    pub fn ends_with(mut self, suffix: &str) -> BasicWordFilters {
+        self.ends_with = self.normalize(suffix);
+        self
+    }
+
+    pub fn contains(mut self, infix: &str) -> BasicWordFilters {
+        self.contains = self.normalize(infix);
+        self
+    }
+
+    /// Shortest word kept, in letters.
+    pub fn min_length(mut self, min_length: usize) -> BasicWordFilters {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Longest word kept, in letters.
+    pub fn max_length(mut self, max_length: usize) -> BasicWordFilters {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Drops words using any of `letters`. Calling this again adds to the
+    /// letters already excluded.
+    pub fn excluding(mut self, letters: &str) -> BasicWordFilters {
+        for letter in self.normalize(letters).chars() {
+            if !self.excluded_letters.contains(&letter) {
+                self.excluded_letters.push(letter);
+            }
+        }
+        self
+    }
 }
 
 impl WordFilters for BasicWordFilters {
     fn passes_filters(&self, word: &str) -> bool {
+        let length = word.chars().count();
+
         word.starts_with(self.starts_with.as_str())
             && word.ends_with(self.ends_with.as_str())
             && word.contains(self.contains.as_str())
+            && length >= self.min_length
+            && self.max_length.is_none_or(|max_length| length <= max_length)
+            && !word.chars().any(|letter| self.excluded_letters.contains(&letter))
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        prefix_matches_start(prefix, &self.starts_with)
+            && self.max_length.is_none_or(|max_length| prefix.chars().count() <= max_length)
+            && !prefix.chars().any(|letter| self.excluded_letters.contains(&letter))
     }
 }
 
@@ -59,4 +210,61 @@ mod test {
         assert!(!filters.passes_filters("caution"));
         assert!(!filters.passes_filters("bobcat"));
     }
+
+    #[test]
+    fn test_basic_word_filters_builder() {
+        let filters = BasicWordFilters::default()
+            .starts_with("RE")
+            .contains("at")
+            .min_length(5)
+            .max_length(7)
+            .excluding("Xs");
+
+        assert!(filters.passes_filters("relate"));
+        assert!(filters.passes_filters("repeat"));
+        assert!(!filters.passes_filters("reat"));
+        assert!(!filters.passes_filters("relating"));
+        assert!(!filters.passes_filters("retreats"));
+        assert!(!filters.passes_filters("relates"));
+        assert!(!filters.passes_filters("berate"));
+        assert!(BasicWordFilters::default().passes_filters("anything"));
+    }
+
+    #[test]
+    fn test_basic_word_filters_may_match_prefix() {
+        let filters = BasicWordFilters::default().starts_with("qu").max_length(5).excluding("z");
+
+        assert!(filters.may_match_prefix(""));
+        assert!(filters.may_match_prefix("q"));
+        assert!(filters.may_match_prefix("quit"));
+        assert!(!filters.may_match_prefix("a"));
+        assert!(!filters.may_match_prefix("quits!"));
+        assert!(!filters.may_match_prefix("quiz"));
+        // The end of a word is unknown until the word is complete
+        assert!(BasicWordFilters::default().ends_with("ing").may_match_prefix("zz"));
+    }
+
+    #[test]
+    fn test_basic_word_filters_excluding_accumulates() {
+        let filters = BasicWordFilters::default().excluding("z").excluding("qz");
+
+        assert_eq!(filters.excluded_letters, vec!['z', 'q']);
+        assert!(!filters.passes_filters("quit"));
+        assert!(!filters.passes_filters("zest"));
+        assert!(filters.passes_filters("test"));
+    }
+
+    #[test]
+    fn test_basic_word_filters_for_alphabet() {
+        let french = Alphabet::french().with_accent_folding();
+        let filters = BasicWordFilters::for_alphabet(&french).contains("FÉ").excluding("Ô");
+        assert!(filters.passes_filters("cafe"));
+        assert!(!filters.passes_filters("face"));
+        assert!(!filters.passes_filters("fetor"));
+        assert!(!BasicWordFilters::default().contains("é").passes_filters("cafe"));
+
+        let filters = BasicWordFilters::for_alphabet(&Alphabet::greek()).starts_with("Λ").ends_with("ς");
+        assert!(filters.passes_filters("λόγοσ"));
+        assert!(!filters.may_match_prefix("μ"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,360 @@
+use std::collections::BTreeSet;
+use std::iter::Peekable;
+use std::rc::Rc;
+use std::str::CharIndices;
+use crate::dictionaries::alphabet::Alphabet;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+
+/// The largest count a repetition such as `{m,n}` may use. No dictionary
+/// word comes close, and larger counts would only cost memory.
+const MAX_REPETITION: usize = 1000;
+
+/// Which letters a single position of a pattern accepts.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Matcher {
+    Any,
+    Letter(char),
+    Class { letters: Vec<char>, negated: bool },
+}
+
+/// A matcher repeated between `min` and `max` times.
+#[derive(Clone, Debug, PartialEq)]
+struct Token {
+    matcher: Matcher,
+    min: usize,
+    max: usize,
+}
+
+/// A crossword-style pattern:
+///
+/// - `?` or `.` is any one letter and `*` is any number of letters,
+/// - `[aeiou]` is one of the listed letters, `[^aeiou]` any other letter, and
+///   `[a-e]` a range of letters,
+/// - `{n}`, `{m,}` and `{m,n}` repeat the letter, wildcard or class before them.
+///
+/// Letters are normalized by the dictionary's alphabet when parsing.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Pattern {
+    tokens: Vec<Token>,
+}
+
+pub(crate) fn invalid(pattern: &str, position: usize, reason: &str) -> Error {
+    Error::InvalidPattern(format!("{} at position {} of {}", reason, position, pattern))
+}
+
+impl Matcher {
+    pub(crate) fn matches(&self, letter: char) -> bool {
+        match self {
+            Matcher::Any => true,
+            Matcher::Letter(expected) => *expected == letter,
+            Matcher::Class { letters, negated } => letters.contains(&letter) != *negated,
+        }
+    }
+}
+
+impl Pattern {
+    pub(crate) fn parse(pattern: &str, alphabet: &Alphabet) -> Result<Pattern, Error> {
+        let normalize = |letter: char| alphabet.normalize(letter).ok_or(Error::NotInAlphabet(letter));
+        let mut chars = pattern.char_indices().peekable();
+        let mut tokens: Vec<Token> = Vec::new();
+
+        while let Some((position, char)) = chars.next() {
+            let matcher = match char {
+                '?' | '.' => Matcher::Any,
+                // Consecutive stars match the same words as a single one
+                '*' if tokens.last().is_some_and(|token| token.min == 0 && token.max == usize::MAX) => continue,
+                '*' => {
+                    tokens.push(Token {
+                        matcher: Matcher::Any,
+                        min: 0,
+                        max: usize::MAX,
+                    });
+                    continue;
+                }
+                '[' => parse_class(pattern, position, &mut chars, normalize)?,
+                '{' => {
+                    let (min, max) = parse_repetition(pattern, position, &mut chars)?;
+                    match tokens.last_mut() {
+                        Some(token) if token.min == 1 && token.max == 1 => {
+                            token.min = min;
+                            token.max = max;
+                        }
+                        _ => return Err(invalid(pattern, position, "Nothing to repeat")),
+                    }
+                    continue;
+                }
+                ']' | '}' => return Err(invalid(pattern, position, "Unmatched bracket")),
+                letter => Matcher::Letter(normalize(letter)?),
+            };
+            tokens.push(Token { matcher, min: 1, max: 1 });
+        }
+
+        Ok(Pattern { tokens })
+    }
+}
+
+/// Parses a character class up to its closing bracket, the opening bracket
+/// at `start` having been read already.
+pub(crate) fn parse_class(
+    pattern: &str,
+    start: usize,
+    chars: &mut Peekable<CharIndices>,
+    normalize: impl Fn(char) -> Result<char, Error>,
+) -> Result<Matcher, Error> {
+    let negated = chars.next_if(|&(_, char)| char == '^').is_some();
+    let mut letters = Vec::new();
+
+    loop {
+        let Some((_, char)) = chars.next() else {
+            return Err(invalid(pattern, start, "Unclosed character class"));
+        };
+        match char {
+            ']' if !letters.is_empty() => break,
+            ']' => return Err(invalid(pattern, start, "Empty character class")),
+            first => {
+                let first = normalize(first)?;
+                let is_range = chars.peek().is_some_and(|&(_, char)| char == '-');
+                if is_range && chars.clone().nth(1).is_some_and(|(_, char)| char != ']') {
+                    chars.next();
+                    let (_, last) = chars.next().unwrap();
+                    let last = normalize(last)?;
+                    letters.extend((first..=last).filter_map(|letter| normalize(letter).ok()));
+                } else {
+                    letters.push(first);
+                }
+            }
+        }
+    }
+    Ok(Matcher::Class { letters, negated })
+}
+
+/// Parses the `m,n` of a repetition up to its closing brace, the opening
+/// brace at `start` having been read already.
+pub(crate) fn parse_repetition(
+    pattern: &str,
+    start: usize,
+    chars: &mut Peekable<CharIndices>,
+) -> Result<(usize, usize), Error> {
+    let mut body = String::new();
+    loop {
+        match chars.next() {
+            Some((_, '}')) => break,
+            Some((_, char)) => body.push(char),
+            None => return Err(invalid(pattern, start, "Unclosed repetition")),
+        }
+    }
+
+    let number = |text: &str| text.trim().parse::<usize>().map_err(|_| invalid(pattern, start, "Invalid repetition"));
+    let (min, max) = match body.split_once(',') {
+        None => {
+            let count = number(&body)?;
+            (count, count)
+        }
+        Some((min, max)) if max.trim().is_empty() => (number(min)?, usize::MAX),
+        Some((min, max)) => (number(min)?, number(max)?),
+    };
+    if min > max {
+        return Err(invalid(pattern, start, "Invalid repetition"));
+    }
+    if min > MAX_REPETITION || (max != usize::MAX && max > MAX_REPETITION) {
+        return Err(invalid(pattern, start, "Repetition too large"));
+    }
+    Ok((min, max))
+}
+
+/// A position in a pattern: a token and how many times it has been repeated.
+/// Repeats of an unbounded token past its minimum are all counted as the
+/// minimum, as they allow the same letters from there on.
+type PatternPosition = (usize, usize);
+
+struct PatternFinderState<'a> {
+    tokens: &'a [Token],
+    current_word: String,
+    words: BTreeSet<String>,
+}
+
+impl PatternFinderState<'_> {
+    /// Adds the positions reached by moving past tokens that have been
+    /// repeated enough, then sorts and dedups the positions.
+    fn skip_tokens(&self, positions: &mut Vec<PatternPosition>) {
+        let mut current = 0;
+        while current < positions.len() {
+            let (index, repeated) = positions[current];
+            if self.tokens.get(index).is_some_and(|token| repeated >= token.min) && !positions.contains(&(index + 1, 0)) {
+                positions.push((index + 1, 0));
+            }
+            current += 1;
+        }
+        positions.sort_unstable();
+        positions.dedup();
+    }
+
+    /// Whether the token at `index` can be repeated once more with `letter`.
+    fn accepts(&self, (index, repeated): PatternPosition, letter: char) -> bool {
+        self.tokens
+            .get(index)
+            .is_some_and(|token| repeated < token.max && token.matcher.matches(letter))
+    }
+
+    /// The positions reached from `positions` by reading `letter`.
+    fn read(&self, positions: &[PatternPosition], letter: char) -> Vec<PatternPosition> {
+        let mut next: Vec<PatternPosition> = positions
+            .iter()
+            .filter(|&&position| self.accepts(position, letter))
+            .map(|&(index, repeated)| match self.tokens[index].max {
+                usize::MAX => (index, (repeated + 1).min(self.tokens[index].min)),
+                _ => (index, repeated + 1),
+            })
+            .collect();
+        self.skip_tokens(&mut next);
+        next
+    }
+}
+
+/// Finds the dictionary words matching a [`Pattern`] by following only the
+/// trie edges the pattern allows at each step. Every position the pattern
+/// can be in is followed at once, so each trie node is visited at most once
+/// however many ways the pattern could match up to it.
+pub(crate) struct PatternWordFinder<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> PatternWordFinder<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> PatternWordFinder<T> {
+        PatternWordFinder { dictionary }
+    }
+
+    /// Returns the matching words in alphabetical order.
+    pub(crate) fn search(&self, pattern: &str) -> Result<Vec<String>, Error> {
+        let pattern = Pattern::parse(pattern, self.dictionary.alphabet())?;
+        Ok(self.search_pattern(&pattern))
+    }
+
+    pub(crate) fn search_pattern(&self, pattern: &Pattern) -> Vec<String> {
+        let mut state = PatternFinderState {
+            tokens: &pattern.tokens,
+            current_word: String::new(),
+            words: BTreeSet::new(),
+        };
+        let mut positions = vec![(0, 0)];
+        state.skip_tokens(&mut positions);
+        let root = self.dictionary.root();
+        self.walk(&root, &positions, &mut state);
+        state.words.into_iter().collect()
+    }
+
+    /// Follows every letter that some of `positions` accept, until no
+    /// position is left.
+    fn walk<'a>(&'a self, node: &T::Node<'a>, positions: &[PatternPosition], state: &mut PatternFinderState) {
+        if positions.contains(&(state.tokens.len(), 0)) && self.dictionary.is_word(node) {
+            state.words.insert(state.current_word.clone());
+        }
+
+        for &letter in self.dictionary.alphabet().letters() {
+            if !positions.iter().any(|&position| state.accepts(position, letter)) {
+                continue;
+            }
+            if let Some(next) = self.dictionary.next(node, letter) {
+                let next_positions = state.read(positions, letter);
+                state.current_word.push(letter);
+                self.walk(&next, &next_positions, state);
+                state.current_word.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+    use std::collections::HashSet;
+    use crate::dictionaries::tries::Trie;
+    use crate::dictionaries::tries::dawg::Dawg;
+
+    fn pattern_word_finder(words: &[&str]) -> PatternWordFinder<HashSet<String>> {
+        PatternWordFinder::new(test_dictionary(words))
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        let alphabet = Alphabet::english();
+        let pattern = Pattern::parse("B.{2,4}[^ae]*", &alphabet).unwrap();
+
+        assert_eq!(
+            pattern.tokens,
+            vec![
+                Token { matcher: Matcher::Letter('b'), min: 1, max: 1 },
+                Token { matcher: Matcher::Any, min: 2, max: 4 },
+                Token { matcher: Matcher::Class { letters: vec!['a', 'e'], negated: true }, min: 1, max: 1 },
+                Token { matcher: Matcher::Any, min: 0, max: usize::MAX },
+            ]
+        );
+        assert_eq!(Pattern::parse("a***", &alphabet).unwrap(), Pattern::parse("a*", &alphabet).unwrap());
+        let pattern = Pattern::parse("[a-c-]", &alphabet);
+        assert!(matches!(pattern, Err(Error::NotInAlphabet('-'))));
+        let pattern = Pattern::parse("[b-d]", &alphabet).unwrap();
+        assert_eq!(pattern.tokens[0].matcher, Matcher::Class { letters: vec!['b', 'c', 'd'], negated: false });
+    }
+
+    #[test]
+    fn test_parse_invalid_patterns() {
+        let alphabet = Alphabet::english();
+
+        for pattern in ["{2}a", "*{2}", "a{2", "a{x}", "a{3,2}", "a{1001}", "a{0,50000000}", "[ae", "[]", "a]"] {
+            assert!(matches!(Pattern::parse(pattern, &alphabet), Err(Error::InvalidPattern(_))), "{}", pattern);
+        }
+        let error = Pattern::parse("ab{", &alphabet).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid pattern: Unclosed repetition at position 2 of ab{");
+        assert!(matches!(Pattern::parse("c1t", &alphabet), Err(Error::NotInAlphabet('1'))));
+    }
+
+    #[test]
+    fn test_pattern_word_finder_search() {
+        let finder = pattern_word_finder(&[
+            "cat", "cot", "cut", "coat", "act", "nation", "station", "ration", "rations", "eyes", "axes", "odds",
+            "bad", "bread", "bird", "bound", "boundard", "bed",
+        ]);
+
+        assert_eq!(finder.search("c?t").unwrap(), vec!["cat", "cot", "cut"]);
+        assert_eq!(finder.search("*ation").unwrap(), vec!["nation", "ration", "station"]);
+        assert_eq!(finder.search("[aeiou]??s").unwrap(), vec!["axes", "eyes", "odds"]);
+        assert_eq!(finder.search("b.{2,4}d").unwrap(), vec!["bird", "bound", "bread"]);
+        assert_eq!(finder.search("C*T").unwrap(), vec!["cat", "coat", "cot", "cut"]);
+        assert_eq!(finder.search("*").unwrap().len(), 18);
+        assert!(finder.search("x*").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_pattern_word_finder_with_dawg() {
+        let mut dawg = Dawg::new();
+        for word in ["bad", "bead", "bed", "bid", "bread"] {
+            dawg.insert(word).unwrap();
+        }
+        dawg.finish();
+        let finder = PatternWordFinder::new(Rc::new(dawg));
+
+        assert_eq!(finder.search("b[ei]d").unwrap(), vec!["bed", "bid"]);
+        assert_eq!(finder.search("b*a*d").unwrap(), vec!["bad", "bead", "bread"]);
+    }
+
+    #[test]
+    fn test_pattern_word_finder_many_wildcards() {
+        let mut dawg = Dawg::new();
+        let long_word = format!("{}b", "a".repeat(40));
+        for word in [long_word.as_str(), "aab", "aae"] {
+            dawg.insert(word).unwrap();
+        }
+        dawg.finish();
+        let finder = PatternWordFinder::new(Rc::new(dawg));
+
+        // Each way of splitting the letters between the stars reaches the
+        // same trie nodes, which are only visited once
+        assert!(finder.search("*a*a*a*a*a*a*a*a*a*a*e").unwrap().is_empty());
+        assert_eq!(finder.search("*a*a*b").unwrap(), vec![long_word.as_str(), "aab"]);
+        assert_eq!(finder.search("*****e").unwrap(), vec!["aae"]);
+        assert_eq!(finder.search("a{2,}[be]").unwrap(), vec![long_word.as_str(), "aab", "aae"]);
+        assert_eq!(finder.search("a{2}?").unwrap(), vec!["aab", "aae"]);
+    }
+}
@@ -0,0 +1,170 @@
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::word_finder::anagram_word_finder::{AnagramWordFinder, Rack};
+use crate::word_finder::BasicWordFilters;
+
+/// Limits for a phrase anagram search.
+#[derive(Clone, Debug)]
+pub struct PhraseAnagramOptions {
+    /// Largest number of words in a single anagram.
+    pub max_words: usize,
+    /// Shortest word allowed in an anagram.
+    pub min_word_length: usize,
+    /// The search stops once this many anagrams have been found.
+    pub max_results: usize,
+}
+
+impl PhraseAnagramOptions {
+    pub fn new(max_words: usize, min_word_length: usize, max_results: usize) -> PhraseAnagramOptions {
+        PhraseAnagramOptions {
+            max_words,
+            min_word_length,
+            max_results,
+        }
+    }
+}
+
+impl Default for PhraseAnagramOptions {
+    fn default() -> PhraseAnagramOptions {
+        PhraseAnagramOptions::new(3, 2, 1000)
+    }
+}
+
+/// A candidate word along with the alphabet slots of its letters.
+struct Candidate {
+    word: String,
+    slots: Vec<usize>,
+}
+
+struct PhraseFinderState<'a> {
+    candidates: &'a [Candidate],
+    words: Vec<usize>,
+    phrases: Vec<String>,
+}
+
+pub(crate) struct PhraseAnagramWordFinder<T: Dictionary> {
+    dictionary: Rc<T>,
+    anagram_word_finder: AnagramWordFinder<T>,
+}
+
+impl<T: Dictionary> PhraseAnagramWordFinder<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> PhraseAnagramWordFinder<T> {
+        PhraseAnagramWordFinder {
+            anagram_word_finder: AnagramWordFinder::new(Rc::clone(&dictionary)),
+            dictionary,
+        }
+    }
+
+    /// Finds anagrams of `phrase` made of dictionary words that together use
+    /// every letter exactly once, joined by spaces. Anagrams with fewer words
+    /// are found first, and words within an anagram are in alphabetical order.
+    pub(crate) fn search(&self, phrase: &str, options: &PhraseAnagramOptions) -> Vec<String> {
+        let alphabet = self.dictionary.alphabet();
+        let mut rack = Rack::new(phrase, alphabet);
+
+        let filters: BasicWordFilters = Default::default();
+        let mut candidates: Vec<Candidate> = self
+            .anagram_word_finder
+            .search_rack(&mut rack, &filters)
+            .into_iter()
+            .map(|anagram_match| anagram_match.word)
+            .filter(|word| word.chars().count() >= options.min_word_length.max(1))
+            .map(|word| Candidate {
+                slots: word.chars().filter_map(|letter| alphabet.index_of(letter)).collect(),
+                word,
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.word.cmp(&b.word));
+
+        let mut state = PhraseFinderState {
+            candidates: &candidates,
+            words: Vec::new(),
+            phrases: Vec::new(),
+        };
+        for word_count in 1..=options.max_words {
+            if state.phrases.len() >= options.max_results {
+                break;
+            }
+            self.find_phrases(0, word_count, &mut rack, &mut state, options);
+        }
+        state.phrases
+    }
+
+    /// Adds candidates from `start` onwards until exactly `word_count` words
+    /// use up the rack. Starting at the current candidate rather than the
+    /// first one keeps each combination of words from being found twice.
+    fn find_phrases(
+        &self,
+        start: usize,
+        word_count: usize,
+        rack: &mut Rack,
+        state: &mut PhraseFinderState,
+        options: &PhraseAnagramOptions,
+    ) {
+        let words_left = word_count - state.words.len();
+        let letters_left = rack.len();
+
+        for index in start..state.candidates.len() {
+            if state.phrases.len() >= options.max_results {
+                return;
+            }
+            let candidate = &state.candidates[index];
+            let length = candidate.slots.len();
+            // The last word has to use every remaining letter, and earlier
+            // ones must leave enough letters for the words still to come.
+            if (words_left == 1 && length != letters_left)
+                || (words_left > 1 && length + (words_left - 1) * options.min_word_length.max(1) > letters_left)
+            {
+                continue;
+            }
+            if !rack.take(&candidate.slots) {
+                continue;
+            }
+
+            state.words.push(index);
+            if words_left == 1 {
+                let words: Vec<&str> = state
+                    .words
+                    .iter()
+                    .map(|&word| state.candidates[word].word.as_str())
+                    .collect();
+                state.phrases.push(words.join(" "));
+            } else {
+                self.find_phrases(index, word_count, rack, state, options);
+            }
+            state.words.pop();
+            rack.put_back(&candidate.slots);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_phrase_anagram_word_finder_search() {
+        let dictionary = HashSet::from([
+            "dirty".to_string(),
+            "room".to_string(),
+            "dormitory".to_string(),
+            "tidy".to_string(),
+            "dry".to_string(),
+            "motor".to_string(),
+            "or".to_string(),
+            "mo".to_string(),
+            "rot".to_string(),
+        ]);
+        let phrase_anagram_word_finder = PhraseAnagramWordFinder::new(Rc::new(dictionary));
+
+        let phrases = phrase_anagram_word_finder.search("dormitory", &PhraseAnagramOptions::new(3, 2, 10));
+        assert_eq!(phrases, vec!["dormitory", "dirty room", "dirty mo or"]);
+
+        let phrases = phrase_anagram_word_finder.search("Dirty Room", &PhraseAnagramOptions::new(3, 3, 10));
+        assert_eq!(phrases, vec!["dormitory", "dirty room"]);
+
+        let phrases = phrase_anagram_word_finder.search("dormitory", &PhraseAnagramOptions::new(2, 2, 1));
+        assert_eq!(phrases, vec!["dormitory"]);
+    }
+}
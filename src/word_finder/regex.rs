@@ -0,0 +1,521 @@
+use std::collections::BTreeSet;
+use std::iter::Peekable;
+use std::rc::Rc;
+use std::str::CharIndices;
+use crate::dictionaries::alphabet::Alphabet;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+use crate::word_finder::pattern_word_finder::{invalid, parse_class, parse_repetition, Matcher};
+use crate::word_finder::WordFilters;
+
+/// The match state is always the first one.
+const MATCH: usize = 0;
+
+/// The most states a regex may compile to, which nested repetitions such as
+/// `(a{1000}){1000}` would otherwise multiply without bound.
+const MAX_STATES: usize = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Anchor {
+    Start,
+    End,
+}
+
+/// A parsed regular expression, before it is compiled into states.
+#[derive(Debug)]
+enum Expression {
+    Empty,
+    Letter(Matcher),
+    Anchor(Anchor),
+    Concat(Vec<Expression>),
+    Alternation(Vec<Expression>),
+    Repeat(Box<Expression>, usize, usize),
+}
+
+/// A state of the automaton. Splits, anchors and the match state are
+/// followed without reading a letter.
+#[derive(Clone, Debug)]
+enum State {
+    Letter(Matcher, usize),
+    Split(usize, usize),
+    Anchor(Anchor, usize),
+    Match,
+}
+
+/// A regular expression matched against whole words, compiled into a
+/// nondeterministic automaton (Thompson's construction).
+///
+/// Supports letters, `.`, character classes such as `[aeiou]`, `[^aeiou]` or
+/// `[a-e]`, groups, alternation with `|`, the repetitions `*`, `+`, `?`,
+/// `{n}`, `{m,}` and `{m,n}`, and the anchors `^` and `$`. As with most regex
+/// engines a word matches if any part of it does, so `ing$` finds the words
+/// ending in "ing" and `^qu` the ones starting with "qu".
+///
+/// As a filter it also prunes prefixes no continuation could match, which
+/// pays off for patterns anchored at the start of the word.
+#[derive(Clone, Debug)]
+pub struct Regex {
+    states: Vec<State>,
+    start: usize,
+    /// Whether each state can still reach the match state past the first letter,
+    /// where `^` can no longer be crossed.
+    live: Vec<bool>,
+}
+
+struct Parser<'a, N: Fn(char) -> Result<char, Error>> {
+    pattern: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    normalize: N,
+}
+
+impl<N: Fn(char) -> Result<char, Error> + Copy> Parser<'_, N> {
+    fn parse_alternation(&mut self) -> Result<Expression, Error> {
+        let mut alternatives = vec![self.parse_concat()?];
+        while self.chars.next_if(|&(_, char)| char == '|').is_some() {
+            alternatives.push(self.parse_concat()?);
+        }
+        Ok(match alternatives.len() {
+            1 => alternatives.pop().unwrap(),
+            _ => Expression::Alternation(alternatives),
+        })
+    }
+
+    fn parse_concat(&mut self) -> Result<Expression, Error> {
+        let mut expressions = Vec::new();
+        while let Some(&(position, char)) = self.chars.peek() {
+            let expression = match char {
+                '|' | ')' => break,
+                '*' | '+' | '?' | '{' => return Err(invalid(self.pattern, position, "Nothing to repeat")),
+                _ => self.parse_atom()?,
+            };
+            expressions.push(self.parse_repetitions(expression)?);
+        }
+        Ok(match expressions.len() {
+            0 => Expression::Empty,
+            1 => expressions.pop().unwrap(),
+            _ => Expression::Concat(expressions),
+        })
+    }
+
+    fn parse_atom(&mut self) -> Result<Expression, Error> {
+        let (position, char) = self.chars.next().unwrap();
+        Ok(match char {
+            '.' => Expression::Letter(Matcher::Any),
+            '^' => Expression::Anchor(Anchor::Start),
+            '$' => Expression::Anchor(Anchor::End),
+            '[' => Expression::Letter(parse_class(self.pattern, position, &mut self.chars, self.normalize)?),
+            '(' => {
+                let expression = self.parse_alternation()?;
+                if self.chars.next_if(|&(_, char)| char == ')').is_none() {
+                    return Err(invalid(self.pattern, position, "Unclosed group"));
+                }
+                expression
+            }
+            ']' | '}' => return Err(invalid(self.pattern, position, "Unmatched bracket")),
+            letter => Expression::Letter(Matcher::Letter((self.normalize)(letter)?)),
+        })
+    }
+
+    fn parse_repetitions(&mut self, mut expression: Expression) -> Result<Expression, Error> {
+        while let Some(&(position, char)) = self.chars.peek() {
+            let (min, max) = match char {
+                '*' => (0, usize::MAX),
+                '+' => (1, usize::MAX),
+                '?' => (0, 1),
+                '{' => {
+                    self.chars.next();
+                    let repetition = parse_repetition(self.pattern, position, &mut self.chars)?;
+                    if let Expression::Anchor(_) = expression {
+                        return Err(invalid(self.pattern, position, "Nothing to repeat"));
+                    }
+                    expression = Expression::Repeat(Box::new(expression), repetition.0, repetition.1);
+                    continue;
+                }
+                _ => break,
+            };
+            if let Expression::Anchor(_) = expression {
+                return Err(invalid(self.pattern, position, "Nothing to repeat"));
+            }
+            self.chars.next();
+            expression = Expression::Repeat(Box::new(expression), min, max);
+        }
+        Ok(expression)
+    }
+}
+
+impl Regex {
+    /// Parses `pattern`, lower casing its letters to match dictionary words.
+    /// Use [`Regex::with_alphabet`] or `WordService::regex` for dictionaries
+    /// that store letters differently.
+    pub fn new(pattern: &str) -> Result<Regex, Error> {
+        Regex::parse(pattern, |letter: char| {
+            if letter.is_alphabetic() {
+                Ok(letter.to_lowercase().next().unwrap_or(letter))
+            } else {
+                Err(Error::NotInAlphabet(letter))
+            }
+        })
+    }
+
+    /// Parses `pattern` with its letters normalized by `alphabet`, as needed
+    /// to filter words of a dictionary folding accents or the final sigma.
+    pub fn with_alphabet(pattern: &str, alphabet: &Alphabet) -> Result<Regex, Error> {
+        Regex::parse(pattern, |letter: char| alphabet.normalize(letter).ok_or(Error::NotInAlphabet(letter)))
+    }
+
+    fn parse(pattern: &str, normalize: impl Fn(char) -> Result<char, Error> + Copy) -> Result<Regex, Error> {
+        let mut parser = Parser {
+            pattern,
+            chars: pattern.char_indices().peekable(),
+            normalize,
+        };
+        let expression = parser.parse_alternation()?;
+        if let Some((position, _)) = parser.chars.next() {
+            return Err(invalid(pattern, position, "Unmatched parenthesis"));
+        }
+
+        let mut states = vec![State::Match];
+        let start = Regex::compile(&expression, MATCH, &mut states)
+            .ok_or_else(|| Error::InvalidPattern(format!("Too many states to compile {}", pattern)))?;
+        let live = Regex::live_states(&states);
+        Ok(Regex { states, start, live })
+    }
+
+    /// Adds the states for `expression`, continuing to `next`, and returns
+    /// the state the expression starts at, or `None` once there would be more
+    /// than `MAX_STATES` states.
+    fn compile(expression: &Expression, next: usize, states: &mut Vec<State>) -> Option<usize> {
+        fn push(state: State, states: &mut Vec<State>) -> Option<usize> {
+            if states.len() >= MAX_STATES {
+                return None;
+            }
+            states.push(state);
+            Some(states.len() - 1)
+        }
+        match expression {
+            Expression::Empty => Some(next),
+            Expression::Letter(matcher) => push(State::Letter(matcher.clone(), next), states),
+            Expression::Anchor(anchor) => push(State::Anchor(*anchor, next), states),
+            Expression::Concat(expressions) => expressions
+                .iter()
+                .rev()
+                .try_fold(next, |next, expression| Regex::compile(expression, next, states)),
+            Expression::Alternation(alternatives) => {
+                let alternatives = alternatives
+                    .iter()
+                    .map(|alternative| Regex::compile(alternative, next, states))
+                    .collect::<Option<Vec<usize>>>()?;
+                alternatives[1..]
+                    .iter()
+                    .try_fold(alternatives[0], |split, &alternative| push(State::Split(split, alternative), states))
+            }
+            Expression::Repeat(expression, min, max) => {
+                let mut next = next;
+                if *max == usize::MAX {
+                    let split = push(State::Split(next, next), states)?;
+                    let body = Regex::compile(expression, split, states)?;
+                    states[split] = State::Split(body, next);
+                    next = split;
+                } else {
+                    let end = next;
+                    for _ in *min..*max {
+                        let body = Regex::compile(expression, next, states)?;
+                        next = push(State::Split(body, end), states)?;
+                    }
+                }
+                for _ in 0..*min {
+                    next = Regex::compile(expression, next, states)?;
+                }
+                Some(next)
+            }
+        }
+    }
+
+    fn live_states(states: &[State]) -> Vec<bool> {
+        let mut live: Vec<bool> = states.iter().map(|state| matches!(state, State::Match)).collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (index, state) in states.iter().enumerate() {
+                let is_live = match *state {
+                    State::Letter(_, next) | State::Anchor(Anchor::End, next) => live[next],
+                    State::Split(first, second) => live[first] || live[second],
+                    State::Anchor(Anchor::Start, _) | State::Match => live[index],
+                };
+                if is_live && !live[index] {
+                    live[index] = true;
+                    changed = true;
+                }
+            }
+        }
+        live
+    }
+
+    /// Adds `state` and every state reachable from it without reading a letter.
+    fn add_state(&self, state: usize, set: &mut Vec<usize>, at_start: bool, at_end: bool) {
+        if set.contains(&state) {
+            return;
+        }
+        set.push(state);
+        match self.states[state] {
+            State::Split(first, second) => {
+                self.add_state(first, set, at_start, at_end);
+                self.add_state(second, set, at_start, at_end);
+            }
+            State::Anchor(Anchor::Start, next) if at_start => self.add_state(next, set, at_start, at_end),
+            State::Anchor(Anchor::End, next) if at_end => self.add_state(next, set, at_start, at_end),
+            _ => {}
+        }
+    }
+
+    /// The states before reading any letter.
+    fn initial_states(&self) -> Vec<usize> {
+        let mut set = Vec::new();
+        self.add_state(self.start, &mut set, true, false);
+        set
+    }
+
+    /// The states after reading `letter`. The start state is added again so
+    /// that a match can begin at any letter of the word, and a match already
+    /// found is kept so that it can end before the word does.
+    fn step(&self, set: &[usize], letter: char) -> Vec<usize> {
+        let mut next_set = Vec::new();
+        if set.contains(&MATCH) {
+            next_set.push(MATCH);
+        }
+        for &state in set {
+            if let State::Letter(ref matcher, next) = self.states[state] {
+                if matcher.matches(letter) {
+                    self.add_state(next, &mut next_set, false, false);
+                }
+            }
+        }
+        self.add_state(self.start, &mut next_set, false, false);
+        next_set
+    }
+
+    fn states_after(&self, word: &str) -> Vec<usize> {
+        word.chars().fold(self.initial_states(), |set, letter| self.step(&set, letter))
+    }
+
+    /// Whether the word read to reach `set` matches, now that it has ended.
+    fn accepts(&self, set: &[usize], at_start: bool) -> bool {
+        let mut end_set = Vec::new();
+        for &state in set {
+            self.add_state(state, &mut end_set, at_start, true);
+        }
+        end_set.iter().any(|&state| matches!(self.states[state], State::Match))
+    }
+
+    /// Whether some continuation of the letters read to reach `set` could match.
+    fn is_viable(&self, set: &[usize]) -> bool {
+        set.iter().any(|&state| self.live[state])
+    }
+}
+
+impl WordFilters for Regex {
+    fn passes_filters(&self, word: &str) -> bool {
+        self.accepts(&self.states_after(word), word.is_empty())
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        prefix.is_empty() || self.live[self.start] || self.is_viable(&self.states_after(prefix))
+    }
+}
+
+struct RegexFinderState {
+    current_word: String,
+    words: BTreeSet<String>,
+}
+
+/// Finds the dictionary words matching a [`Regex`] by running its automaton
+/// alongside the trie, so only prefixes the automaton can continue are visited.
+pub(crate) struct RegexWordFinder<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> RegexWordFinder<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> RegexWordFinder<T> {
+        RegexWordFinder { dictionary }
+    }
+
+    /// Returns the matching words in alphabetical order.
+    pub(crate) fn search(&self, pattern: &str) -> Result<Vec<String>, Error> {
+        let regex = Regex::with_alphabet(pattern, self.dictionary.alphabet())?;
+        Ok(self.search_regex(&regex))
+    }
+
+    pub(crate) fn search_regex(&self, regex: &Regex) -> Vec<String> {
+        let mut state = RegexFinderState {
+            current_word: String::new(),
+            words: BTreeSet::new(),
+        };
+        let root = self.dictionary.root();
+        self.walk(&root, &regex.initial_states(), regex, &mut state);
+        state.words.into_iter().collect()
+    }
+
+    fn walk<'a>(&'a self, node: &T::Node<'a>, set: &[usize], regex: &Regex, state: &mut RegexFinderState) {
+        if !state.current_word.is_empty() && self.dictionary.is_word(node) && regex.accepts(set, false) {
+            state.words.insert(state.current_word.clone());
+        }
+
+        for &letter in self.dictionary.alphabet().letters() {
+            let Some(next) = self.dictionary.next(node, letter) else {
+                continue;
+            };
+            let next_set = regex.step(set, letter);
+            if regex.is_viable(&next_set) {
+                state.current_word.push(letter);
+                self.walk(&next, &next_set, regex, state);
+                state.current_word.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+    use std::time::Instant;
+    use crate::SCRABBLE_DICTIONARY_PATH;
+    use crate::dictionaries::tries::Trie;
+    use crate::dictionaries::tries::trie::SimpleTrie;
+    use crate::dictionaries::tries::trie_builder::{TrieBuilder, TxtFileTrieBuilder};
+    use crate::word_finder::anagram_word_finder::AnagramWordFinder;
+    use crate::word_finder::matrix::Matrix;
+    use crate::word_finder::matrix_word_finder::MatrixWordFinder;
+    use crate::word_finder::{test_dictionary, WordFinder};
+
+    #[test]
+    fn test_regex_passes_filters() {
+        let regex = Regex::new("^(re|un)[a-z]+(ed|ing)$").unwrap();
+        assert!(regex.passes_filters("undoing"));
+        assert!(regex.passes_filters("replayed"));
+        assert!(!regex.passes_filters("redo"));
+        assert!(!regex.passes_filters("uning"));
+        assert!(!regex.passes_filters("preplayed"));
+
+        let regex = Regex::new("ing$").unwrap();
+        assert!(regex.passes_filters("singing"));
+        assert!(!regex.passes_filters("singer"));
+
+        let regex = Regex::new("q[^u]").unwrap();
+        assert!(regex.passes_filters("qat"));
+        assert!(regex.passes_filters("niqab"));
+        assert!(!regex.passes_filters("queen"));
+
+        let regex = Regex::new("^b(an)+a?s?$").unwrap();
+        assert!(regex.passes_filters("banana"));
+        assert!(regex.passes_filters("bananas"));
+        assert!(!regex.passes_filters("bananna"));
+
+        let regex = Regex::new("^a.{2,3}e$").unwrap();
+        assert!(regex.passes_filters("apple"));
+        assert!(regex.passes_filters("axle"));
+        assert!(!regex.passes_filters("ace"));
+        assert!(!regex.passes_filters("airline"));
+
+        let regex = Regex::new("^(a|)b*$").unwrap();
+        assert!(regex.passes_filters("abb"));
+        assert!(regex.passes_filters("bbb"));
+        assert!(!regex.passes_filters("aab"));
+        assert!(!Regex::new("a^b").unwrap().passes_filters("ab"));
+    }
+
+    #[test]
+    fn test_regex_may_match_prefix() {
+        let regex = Regex::new("^qu(ick|een)$").unwrap();
+        assert!(regex.may_match_prefix(""));
+        assert!(regex.may_match_prefix("q"));
+        assert!(regex.may_match_prefix("quic"));
+        assert!(!regex.may_match_prefix("qa"));
+        assert!(!regex.may_match_prefix("queens"));
+        assert!(!regex.may_match_prefix("aqu"));
+
+        // Unanchored patterns can start matching at any letter
+        assert!(Regex::new("qu").unwrap().may_match_prefix("zzz"));
+        assert!(!Regex::new("a^b").unwrap().may_match_prefix("a"));
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        for pattern in ["(ab", "ab)", "*a", "a|+", "a{2", "[ab", "^*", "a{3,1}"] {
+            assert!(matches!(Regex::new(pattern), Err(Error::InvalidPattern(_))), "{}", pattern);
+        }
+        assert!(matches!(Regex::new("a1"), Err(Error::NotInAlphabet('1'))));
+
+        // Repetitions are capped, and so is the automaton they compile to
+        assert!(matches!(Regex::new("^a{0,50000000}$"), Err(Error::InvalidPattern(_))));
+        let error = Regex::new("(a{1000}){1000}").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid pattern: Too many states to compile (a{1000}){1000}");
+        assert!(Regex::new("^(ab){2,1000}$").unwrap().passes_filters("ababab"));
+    }
+
+    #[test]
+    fn test_regex_with_alphabet() {
+        let french = Alphabet::french().with_accent_folding();
+        assert!(Regex::with_alphabet("^CAFÉ$", &french).unwrap().passes_filters("cafe"));
+        assert!(!Regex::new("^café$").unwrap().passes_filters("cafe"));
+        assert!(Regex::with_alphabet("ος$", &Alphabet::greek()).unwrap().passes_filters("λόγοσ"));
+        assert!(matches!(Regex::with_alphabet("ñ", &Alphabet::english()), Err(Error::NotInAlphabet('ñ'))));
+    }
+
+    #[test]
+    fn test_regex_word_finder_with_simple_trie() {
+        let mut trie = SimpleTrie::new();
+        for word in ["quick", "queen", "quiet", "equip", "squid", "aqua", "quo"] {
+            trie.insert(word).unwrap();
+        }
+        let regex_word_finder = RegexWordFinder::new(Rc::new(trie));
+
+        assert_eq!(regex_word_finder.search("^qu(i|ee)").unwrap(), vec!["queen", "quick", "quiet"]);
+        assert_eq!(regex_word_finder.search("qui").unwrap(), vec!["equip", "quick", "quiet", "squid"]);
+        assert_eq!(regex_word_finder.search("u.?$").unwrap(), vec!["aqua", "quo"]);
+    }
+
+    #[test]
+    fn test_regex_as_filter_for_finders() {
+        let dictionary = test_dictionary(&["rat", "tar", "art", "star", "rats", "arts", "tsar"]);
+        let regex = Regex::new("^(t|s)a").unwrap();
+
+        let anagram_word_finder = AnagramWordFinder::new(Rc::clone(&dictionary));
+        let words = anagram_word_finder.search(&"rats".to_string(), &regex);
+        assert_eq!(words, HashSet::from(["tar".to_string()]));
+
+        let matrix = Matrix::new(&[&['s', 't', 'a'], &['x', 'x', 'r']]).unwrap();
+        let matrix_word_finder = MatrixWordFinder::new(dictionary, true);
+        let words = matrix_word_finder.search(&matrix, &Regex::new("r$").unwrap());
+        assert_eq!(words, HashSet::from(["star".to_string(), "tar".to_string()]));
+    }
+
+    #[test]
+    fn test_regex_word_finder_compared_to_filtering_every_word() {
+        let words = std::fs::read_to_string(SCRABBLE_DICTIONARY_PATH).unwrap().to_lowercase();
+        let mut trie = SimpleTrie::new();
+        TxtFileTrieBuilder::new(SCRABBLE_DICTIONARY_PATH).unwrap().build(&mut trie).unwrap();
+        let regex_word_finder = RegexWordFinder::new(Rc::new(trie));
+
+        for pattern in ["^(ex|in)[a-z]{2,4}(ed|al)$", "^qu[aeiou]{3}", "^[^aeiou]+y$"] {
+            let regex = Regex::new(pattern).unwrap();
+
+            let start_time = Instant::now();
+            let expected: Vec<&str> = words.lines().filter(|word| regex.passes_filters(word)).collect();
+            let scan_time = Instant::now() - start_time;
+
+            let start_time = Instant::now();
+            let found = regex_word_finder.search(pattern).unwrap();
+            let automaton_time = Instant::now() - start_time;
+
+            println!(
+                "{}: scanning every word {:?}, walking the trie {:?}, {} words",
+                pattern,
+                scan_time,
+                automaton_time,
+                found.len()
+            );
+            assert_eq!(found, expected);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use crate::dictionaries::alphabet::fold_accent;
+
+/// Points of each tile in a word game. Accented letters are worth as much as
+/// the tile they are played with, and letters missing from a table are
+/// worth nothing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LetterValues {
+    values: HashMap<char, u32>,
+}
+
+/// A word along with its points, as returned by [`LetterValues::rank`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScoredWord {
+    pub word: String,
+    pub score: u32,
+}
+
+impl LetterValues {
+    /// Builds a table from groups of letters worth the same points, such as
+    /// `&[("aeilnorstu", 1), ("dg", 2)]`.
+    pub fn new(groups: &[(&str, u32)]) -> LetterValues {
+        let mut values = HashMap::new();
+        for &(letters, points) in groups {
+            for letter in letters.to_lowercase().chars() {
+                values.insert(letter, points);
+            }
+        }
+        LetterValues { values }
+    }
+
+    pub fn scrabble_english() -> LetterValues {
+        LetterValues::new(&[
+            ("aeilnorstu", 1),
+            ("dg", 2),
+            ("bcmp", 3),
+            ("fhvwy", 4),
+            ("k", 5),
+            ("jx", 8),
+            ("qz", 10),
+        ])
+    }
+
+    pub fn words_with_friends() -> LetterValues {
+        LetterValues::new(&[
+            ("aeiorst", 1),
+            ("dlnu", 2),
+            ("ghy", 3),
+            ("bcfmpw", 4),
+            ("kv", 5),
+            ("x", 8),
+            ("jqz", 10),
+        ])
+    }
+
+    /// Spanish Scrabble without its CH, LL and RR tiles, which the
+    /// dictionaries spell as two letters.
+    pub fn scrabble_spanish() -> LetterValues {
+        LetterValues::new(&[
+            ("aeioslnrtuáéíóúü", 1),
+            ("dg", 2),
+            ("bcmp", 3),
+            ("fhvy", 4),
+            ("q", 5),
+            ("jñx", 8),
+            ("z", 10),
+        ])
+    }
+
+    /// German Scrabble, where ß is played as two S tiles.
+    pub fn scrabble_german() -> LetterValues {
+        LetterValues::new(&[
+            ("adeinrstu", 1),
+            ("ghloß", 2),
+            ("bmwz", 3),
+            ("cfkp", 4),
+            ("äjüv", 6),
+            ("öx", 8),
+            ("qy", 10),
+        ])
+    }
+
+    pub fn scrabble_french() -> LetterValues {
+        LetterValues::new(&[
+            ("aeilnorstuàâæéèêëîïôœùûü", 1),
+            ("dgm", 2),
+            ("bcpç", 3),
+            ("fhv", 4),
+            ("jq", 8),
+            ("kwxyzÿ", 10),
+        ])
+    }
+
+    pub fn scrabble_greek() -> LetterValues {
+        LetterValues::new(&[
+            ("αεηινοσςτάέήίόϊΐ", 1),
+            ("κπρυύϋΰ", 2),
+            ("λμωώ", 3),
+            ("γδ", 4),
+            ("βφχ", 8),
+            ("ζθξψ", 10),
+        ])
+    }
+
+    /// The points of `letter`, or of its base letter if the table has no
+    /// tile of its own for it, so "é" is worth an E in English.
+    pub fn value(&self, letter: char) -> u32 {
+        let letter = letter.to_lowercase().next().unwrap_or(letter);
+        self.values
+            .get(&letter)
+            .or_else(|| self.values.get(&fold_accent(letter)))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn score(&self, word: &str) -> u32 {
+        word.chars().map(|letter| self.value(letter)).sum()
+    }
+
+    /// Scores the words and sorts them best first: by score, then by length,
+    /// then alphabetically.
+    pub fn rank<I: IntoIterator<Item = String>>(&self, words: I) -> Vec<ScoredWord> {
+        let mut ranked: Vec<ScoredWord> = words
+            .into_iter()
+            .map(|word| ScoredWord { score: self.score(&word), word })
+            .collect();
+        ranked.sort_by(|a, b| {
+            (Reverse(a.score), Reverse(a.word.chars().count()), &a.word)
+                .cmp(&(Reverse(b.score), Reverse(b.word.chars().count()), &b.word))
+        });
+        ranked
+    }
+}
+
+impl Default for LetterValues {
+    fn default() -> LetterValues {
+        LetterValues::scrabble_english()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dictionaries::alphabet::Alphabet;
+
+    #[test]
+    fn test_letter_values() {
+        let english = LetterValues::scrabble_english();
+        assert_eq!(english.score("quiz"), 22);
+        assert_eq!(LetterValues::words_with_friends().score("quiz"), 23);
+        assert_eq!(LetterValues::scrabble_german().score("über"), 11);
+        assert_eq!(LetterValues::scrabble_french().score("été"), 3);
+        assert_eq!(LetterValues::scrabble_spanish().score("año"), 10);
+        assert_eq!(LetterValues::scrabble_greek().score("λόγος"), 10);
+        assert_eq!(LetterValues::scrabble_greek().score("ΛΟΓΟΣ"), 10);
+    }
+
+    #[test]
+    fn test_accented_letters_use_their_base_tile() {
+        let english = LetterValues::scrabble_english();
+        assert_eq!(english.value('é'), english.value('e'));
+        assert_eq!(english.score("Café"), english.score("cafe"));
+        // Letters with a tile of their own keep its value
+        assert_eq!(LetterValues::scrabble_spanish().value('ñ'), 8);
+        assert_eq!(LetterValues::scrabble_german().value('ö'), 8);
+        assert_eq!(english.value('1'), 0);
+    }
+
+    #[test]
+    fn test_tables_cover_their_alphabets() {
+        let tables = [
+            (LetterValues::scrabble_english(), Alphabet::english()),
+            (LetterValues::words_with_friends(), Alphabet::english()),
+            (LetterValues::scrabble_german(), Alphabet::german()),
+            (LetterValues::scrabble_french(), Alphabet::french()),
+            (LetterValues::scrabble_greek(), Alphabet::greek()),
+        ];
+        for (values, alphabet) in tables {
+            let missing: Vec<char> = alphabet.letters().iter().copied().filter(|&letter| values.value(letter) == 0).collect();
+            assert!(missing.is_empty(), "{:?}", missing);
+        }
+    }
+
+    #[test]
+    fn test_rank_words() {
+        let words = ["tea", "eat", "cat", "at", "cats", "act"].map(String::from);
+        let ranked = LetterValues::scrabble_english().rank(words);
+        let order: Vec<&str> = ranked.iter().map(|scored| scored.word.as_str()).collect();
+
+        assert_eq!(order, vec!["cats", "act", "cat", "eat", "tea", "at"]);
+        assert_eq!(ranked[0], ScoredWord { word: "cats".to_string(), score: 6 });
+    }
+}
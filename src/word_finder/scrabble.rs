@@ -0,0 +1,708 @@
+use std::cmp::Reverse;
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+use crate::word_finder::anagram_word_finder::Rack;
+use crate::word_finder::matrix::{Direction, Point};
+use crate::word_finder::scoring::LetterValues;
+
+/// Number of rows and columns of a Scrabble board.
+pub const BOARD_SIZE: usize = 15;
+const CENTER: Point = (7, 7);
+/// Most tiles a rack can hold.
+pub const RACK_SIZE: usize = 7;
+/// Extra points for playing every tile of a full rack.
+pub const BINGO_BONUS: u32 = 50;
+
+/// The bonus printed on a square, which only counts for the tile placed on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Premium {
+    None,
+    DoubleLetter,
+    TripleLetter,
+    DoubleWord,
+    TripleWord,
+}
+
+impl Premium {
+    /// The premium of a square of the standard board. The layout is symmetric
+    /// about both axes and both diagonals, so only one eighth of it is listed.
+    /// Squares off the board have no premium.
+    pub fn at(point: Point) -> Premium {
+        if !is_on_board(point) {
+            return Premium::None;
+        }
+        let fold = |index: usize| index.min(BOARD_SIZE - 1 - index);
+        let (row, column) = (fold(point.0), fold(point.1));
+        match (row.min(column), row.max(column)) {
+            (0, 0) | (0, 7) => Premium::TripleWord,
+            (1, 1) | (2, 2) | (3, 3) | (4, 4) | (7, 7) => Premium::DoubleWord,
+            (1, 5) | (5, 5) => Premium::TripleLetter,
+            (0, 3) | (2, 6) | (3, 7) | (6, 6) => Premium::DoubleLetter,
+            _ => Premium::None,
+        }
+    }
+
+    fn letter_multiplier(self) -> u32 {
+        match self {
+            Premium::DoubleLetter => 2,
+            Premium::TripleLetter => 3,
+            _ => 1,
+        }
+    }
+
+    fn word_multiplier(self) -> u32 {
+        match self {
+            Premium::DoubleWord => 2,
+            Premium::TripleWord => 3,
+            _ => 1,
+        }
+    }
+}
+
+/// A tile put down by a move. Blanks are worth nothing whatever letter they
+/// stand for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PlacedTile {
+    pub point: Point,
+    pub letter: char,
+    pub is_blank: bool,
+}
+
+/// A legal placement of tiles from the rack and the points it scores.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScrabbleMove {
+    /// The word read along `direction`, including the tiles already on the board.
+    pub word: String,
+    pub start: Point,
+    /// Either [`Direction::Right`] or [`Direction::Down`].
+    pub direction: Direction,
+    /// The tiles taken from the rack, in the order they are read.
+    pub tiles: Vec<PlacedTile>,
+    /// The words the placed tiles form across the main word.
+    pub cross_words: Vec<String>,
+    pub score: u32,
+}
+
+/// The tiles on a standard 15x15 board, each remembering whether it is a blank.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScrabbleBoard {
+    squares: Vec<Option<(char, bool)>>,
+}
+
+impl Default for ScrabbleBoard {
+    fn default() -> ScrabbleBoard {
+        ScrabbleBoard::new()
+    }
+}
+
+impl ScrabbleBoard {
+    pub fn new() -> ScrabbleBoard {
+        ScrabbleBoard {
+            squares: vec![None; BOARD_SIZE * BOARD_SIZE],
+        }
+    }
+
+    /// Reads a board from 15 rows of 15 squares, where `.` or a space is an
+    /// empty square, an upper case letter a tile and a lower case letter a
+    /// blank standing for that letter.
+    pub fn from_rows(rows: &[&str]) -> Result<ScrabbleBoard, Error> {
+        if rows.len() != BOARD_SIZE {
+            return Err(Error::BoardSize {
+                expected: BOARD_SIZE,
+                found: rows.len(),
+            });
+        }
+
+        let mut board = ScrabbleBoard::new();
+        for (row, line) in rows.iter().enumerate() {
+            let width = line.chars().count();
+            if width != BOARD_SIZE {
+                return Err(Error::RaggedGrid {
+                    row,
+                    expected: BOARD_SIZE,
+                    found: width,
+                });
+            }
+
+            for (column, square) in line.chars().enumerate() {
+                board.squares[row * BOARD_SIZE + column] = match square {
+                    '.' | ' ' => None,
+                    letter if letter.is_alphabetic() => {
+                        Some((letter.to_lowercase().next().unwrap_or(letter), letter.is_lowercase()))
+                    }
+                    character => return Err(Error::InvalidSquare { row, column, character }),
+                };
+            }
+        }
+        Ok(board)
+    }
+
+    /// The letter on the square, in lower case, if it holds a tile. Squares
+    /// off the board hold none.
+    pub fn tile(&self, point: Point) -> Option<char> {
+        self.square(point).map(|(letter, _)| letter)
+    }
+
+    pub fn is_blank(&self, point: Point) -> bool {
+        self.square(point).is_some_and(|(_, is_blank)| is_blank)
+    }
+
+    fn square(&self, point: Point) -> Option<(char, bool)> {
+        if is_on_board(point) {
+            self.squares[index(point)]
+        } else {
+            None
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.squares.iter().all(Option::is_none)
+    }
+
+    /// Puts the tiles of a move on the board, or none of them if any would
+    /// be off the board.
+    pub fn play(&mut self, scrabble_move: &ScrabbleMove) -> Result<(), Error> {
+        if let Some(tile) = scrabble_move.tiles.iter().find(|tile| !is_on_board(tile.point)) {
+            return Err(Error::InvalidPuzzle(format!("square {:?} is off the board", tile.point)));
+        }
+        for tile in &scrabble_move.tiles {
+            self.squares[index(tile.point)] = Some((tile.letter, tile.is_blank));
+        }
+        Ok(())
+    }
+
+    /// Squares a move has to cover one of: those next to a tile, or the
+    /// centre square on an empty board.
+    fn is_anchor(&self, point: Point) -> bool {
+        if self.tile(point).is_some() {
+            return false;
+        }
+        if self.is_empty() {
+            return point == CENTER;
+        }
+        [Direction::Up, Direction::Down, Direction::Left, Direction::Right]
+            .into_iter()
+            .filter_map(|direction| step(point, direction))
+            .any(|neighbour| self.tile(neighbour).is_some())
+    }
+
+    fn tile_value(&self, point: Point, letter_values: &LetterValues) -> u32 {
+        match self.squares[index(point)] {
+            Some((_, true)) | None => 0,
+            Some((letter, false)) => letter_values.value(letter),
+        }
+    }
+}
+
+fn is_on_board(point: Point) -> bool {
+    point.0 < BOARD_SIZE && point.1 < BOARD_SIZE
+}
+
+fn index(point: Point) -> usize {
+    point.0 * BOARD_SIZE + point.1
+}
+
+/// The next square towards one of the four straight directions, if still on the board.
+fn step(point: Point, direction: Direction) -> Option<Point> {
+    let (row, column) = point;
+    match direction {
+        Direction::Up => row.checked_sub(1).map(|row| (row, column)),
+        Direction::Down => (row + 1 < BOARD_SIZE).then_some((row + 1, column)),
+        Direction::Left => column.checked_sub(1).map(|column| (row, column)),
+        Direction::Right => (column + 1 < BOARD_SIZE).then_some((row, column + 1)),
+        _ => None,
+    }
+}
+
+/// The directions reading backwards along a move and across it, before and after.
+fn orientation(direction: Direction) -> (Direction, Direction, Direction) {
+    match direction {
+        Direction::Down => (Direction::Up, Direction::Left, Direction::Right),
+        _ => (Direction::Left, Direction::Up, Direction::Down),
+    }
+}
+
+/// What the word across a move allows on an empty square.
+#[derive(Clone, Debug, Default)]
+struct CrossCheck {
+    /// The letters completing a word across, or `None` when no tile touches
+    /// the square across the move.
+    allowed: Option<Vec<char>>,
+    before: String,
+    after: String,
+    /// Points of the tiles already in the word across.
+    score: u32,
+}
+
+struct MoveGeneratorState<'a> {
+    board: &'a ScrabbleBoard,
+    letter_values: &'a LetterValues,
+    direction: Direction,
+    cross_checks: Vec<CrossCheck>,
+    anchors: Vec<bool>,
+    rack: Rack,
+    start: Point,
+    word: String,
+    tiles: Vec<PlacedTile>,
+    moves: Vec<ScrabbleMove>,
+}
+
+impl MoveGeneratorState<'_> {
+    fn record_move(&mut self) {
+        if self.word.chars().count() < 2 {
+            return;
+        }
+        // A single tile forming words both ways is recorded across only
+        if self.direction == Direction::Down
+            && self.tiles.len() == 1
+            && self.cross_checks[index(self.tiles[0].point)].allowed.is_some()
+        {
+            return;
+        }
+
+        let mut score = 0;
+        let mut multiplier = 1;
+        let mut cross_score = 0;
+        let mut cross_words = Vec::new();
+        let mut point = self.start;
+        for letter in self.word.chars() {
+            match self.tiles.iter().find(|tile| tile.point == point) {
+                Some(tile) => {
+                    let premium = Premium::at(point);
+                    let value = if tile.is_blank { 0 } else { self.letter_values.value(letter) } * premium.letter_multiplier();
+                    score += value;
+                    multiplier *= premium.word_multiplier();
+
+                    let cross_check = &self.cross_checks[index(point)];
+                    if cross_check.allowed.is_some() {
+                        cross_score += (cross_check.score + value) * premium.word_multiplier();
+                        cross_words.push(format!("{}{}{}", cross_check.before, letter, cross_check.after));
+                    }
+                }
+                None => score += self.board.tile_value(point, self.letter_values),
+            }
+            point = step(point, self.direction).unwrap_or(point);
+        }
+
+        let bonus = if self.tiles.len() == RACK_SIZE { BINGO_BONUS } else { 0 };
+        self.moves.push(ScrabbleMove {
+            word: self.word.clone(),
+            start: self.start,
+            direction: self.direction,
+            tiles: self.tiles.clone(),
+            cross_words,
+            score: score * multiplier + cross_score + bonus,
+        });
+    }
+}
+
+/// Generates every legal Scrabble move for a rack, by extending words from
+/// each square through the dictionary while checking the words formed across.
+pub(crate) struct ScrabbleMoveGenerator<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> ScrabbleMoveGenerator<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> ScrabbleMoveGenerator<T> {
+        ScrabbleMoveGenerator { dictionary }
+    }
+
+    /// Returns the moves from highest to lowest score. Blanks in the rack are
+    /// written as [`crate::BLANK`]. Fails if the rack holds more than
+    /// [`RACK_SIZE`] tiles or a letter outside the dictionary's alphabet.
+    pub(crate) fn generate(
+        &self,
+        board: &ScrabbleBoard,
+        rack: &str,
+        letter_values: &LetterValues,
+    ) -> Result<Vec<ScrabbleMove>, Error> {
+        let alphabet = self.dictionary.alphabet();
+        let tiles = rack.chars().count();
+        if tiles > RACK_SIZE {
+            return Err(Error::InvalidPuzzle(format!("{} tiles is more than a rack holds", tiles)));
+        }
+        Rack::check_tiles(rack, alphabet)?;
+        let anchors: Vec<bool> = (0..BOARD_SIZE * BOARD_SIZE)
+            .map(|index| board.is_anchor((index / BOARD_SIZE, index % BOARD_SIZE)))
+            .collect();
+        let mut moves = Vec::new();
+
+        for direction in [Direction::Right, Direction::Down] {
+            let mut state = MoveGeneratorState {
+                board,
+                letter_values,
+                direction,
+                cross_checks: self.cross_checks(board, direction, letter_values),
+                anchors: anchors.clone(),
+                rack: Rack::with_blanks(rack, alphabet),
+                start: CENTER,
+                word: String::new(),
+                tiles: Vec::new(),
+                moves: Vec::new(),
+            };
+            let (backwards, _, _) = orientation(direction);
+
+            for index in 0..BOARD_SIZE * BOARD_SIZE {
+                let start = (index / BOARD_SIZE, index % BOARD_SIZE);
+                let follows_tile = step(start, backwards).is_some_and(|point| board.tile(point).is_some());
+                if !follows_tile && self.reaches_anchor(start, &state) {
+                    state.start = start;
+                    self.extend(Some(start), &self.dictionary.root(), false, &mut state);
+                }
+            }
+            moves.append(&mut state.moves);
+        }
+
+        moves.sort_by_key(|scrabble_move| Reverse(scrabble_move.score));
+        Ok(moves)
+    }
+
+    /// Whether the rack has enough tiles to cover an anchor when starting at `start`.
+    fn reaches_anchor(&self, start: Point, state: &MoveGeneratorState) -> bool {
+        let mut empty_squares = 0;
+        let mut point = Some(start);
+        while let Some(current) = point {
+            if state.board.tile(current).is_none() {
+                empty_squares += 1;
+                if empty_squares > state.rack.len() {
+                    return false;
+                }
+                if state.anchors[index(current)] {
+                    return true;
+                }
+            }
+            point = step(current, state.direction);
+        }
+        false
+    }
+
+    /// Reads the tiles next to `point` towards `direction`, returning their
+    /// letters in reading order and their points.
+    fn read_tiles(
+        &self,
+        board: &ScrabbleBoard,
+        point: Point,
+        direction: Direction,
+        letter_values: &LetterValues,
+    ) -> (String, u32) {
+        let alphabet = self.dictionary.alphabet();
+        let mut letters = Vec::new();
+        let mut score = 0;
+        let mut next = step(point, direction);
+        while let Some(current) = next {
+            let Some(letter) = board.tile(current) else {
+                break;
+            };
+            letters.push(alphabet.normalize(letter).unwrap_or(letter));
+            score += board.tile_value(current, letter_values);
+            next = step(current, direction);
+        }
+        if matches!(direction, Direction::Up | Direction::Left) {
+            letters.reverse();
+        }
+        (letters.into_iter().collect(), score)
+    }
+
+    fn cross_checks(&self, board: &ScrabbleBoard, direction: Direction, letter_values: &LetterValues) -> Vec<CrossCheck> {
+        let (_, before, after) = orientation(direction);
+        (0..BOARD_SIZE * BOARD_SIZE)
+            .map(|index| {
+                let point = (index / BOARD_SIZE, index % BOARD_SIZE);
+                if board.tile(point).is_some() {
+                    return CrossCheck::default();
+                }
+                let (before, before_score) = self.read_tiles(board, point, before, letter_values);
+                let (after, after_score) = self.read_tiles(board, point, after, letter_values);
+                if before.is_empty() && after.is_empty() {
+                    return CrossCheck::default();
+                }
+
+                let allowed = self
+                    .dictionary
+                    .alphabet()
+                    .letters()
+                    .iter()
+                    .copied()
+                    .filter(|letter| self.dictionary.search(&format!("{}{}{}", before, letter, after)))
+                    .collect();
+                CrossCheck {
+                    allowed: Some(allowed),
+                    before,
+                    after,
+                    score: before_score + after_score,
+                }
+            })
+            .collect()
+    }
+
+    /// Extends the word read so far onto `point`, following the tile on it or
+    /// trying each letter of the rack that fits the word across.
+    fn extend<'a>(&'a self, point: Option<Point>, node: &T::Node<'a>, covers_anchor: bool, state: &mut MoveGeneratorState) {
+        let alphabet = self.dictionary.alphabet();
+        if let Some(letter) = point.and_then(|point| state.board.tile(point)) {
+            let letter = alphabet.normalize(letter).unwrap_or(letter);
+            if let Some(next) = self.dictionary.next(node, letter) {
+                state.word.push(letter);
+                self.extend(step(point.unwrap(), state.direction), &next, covers_anchor, state);
+                state.word.pop();
+            }
+            return;
+        }
+
+        if covers_anchor && !state.tiles.is_empty() && self.dictionary.is_word(node) {
+            state.record_move();
+        }
+        let Some(point) = point else {
+            return;
+        };
+        let covers_anchor = covers_anchor || state.anchors[index(point)];
+        let letters: Vec<char> = match &state.cross_checks[index(point)].allowed {
+            Some(allowed) => allowed.clone(),
+            None => alphabet.letters().to_vec(),
+        };
+
+        for letter in letters {
+            let Some(next) = self.dictionary.next(node, letter) else {
+                continue;
+            };
+            let slot = alphabet.index_of(letter).unwrap();
+            if state.rack.take(&[slot]) {
+                self.place(point, letter, false, &next, covers_anchor, state);
+                state.rack.put_back(&[slot]);
+            }
+            if state.rack.take_blank() {
+                self.place(point, letter, true, &next, covers_anchor, state);
+                state.rack.put_back_blank();
+            }
+        }
+    }
+
+    fn place<'a>(
+        &'a self,
+        point: Point,
+        letter: char,
+        is_blank: bool,
+        node: &T::Node<'a>,
+        covers_anchor: bool,
+        state: &mut MoveGeneratorState,
+    ) {
+        state.tiles.push(PlacedTile { point, letter, is_blank });
+        state.word.push(letter);
+        self.extend(step(point, state.direction), node, covers_anchor, state);
+        state.word.pop();
+        state.tiles.pop();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+    use std::collections::HashSet;
+    use crate::dictionaries::tries::Trie;
+    use crate::dictionaries::tries::dawg::Dawg;
+    use crate::dictionaries::tries::trie_builder::{TrieBuilder, TxtFileTrieBuilder};
+    use crate::SCRABBLE_DICTIONARY_PATH;
+
+    fn move_generator(words: &[&str]) -> ScrabbleMoveGenerator<HashSet<String>> {
+        ScrabbleMoveGenerator::new(test_dictionary(words))
+    }
+
+    /// An empty board apart from `word` played across from `start`.
+    fn board_with(word: &str, start: Point) -> ScrabbleBoard {
+        let mut rows = vec![".".repeat(BOARD_SIZE); BOARD_SIZE];
+        let row = &mut rows[start.0];
+        row.replace_range(start.1..start.1 + word.len(), word);
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+        ScrabbleBoard::from_rows(&rows).unwrap()
+    }
+
+    fn find_move<'a>(moves: &'a [ScrabbleMove], word: &str, start: Point, direction: Direction) -> &'a ScrabbleMove {
+        moves
+            .iter()
+            .find(|scrabble_move| {
+                scrabble_move.word == word && scrabble_move.start == start && scrabble_move.direction == direction
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_premium_layout() {
+        assert_eq!(Premium::at((0, 0)), Premium::TripleWord);
+        assert_eq!(Premium::at((14, 7)), Premium::TripleWord);
+        assert_eq!(Premium::at((7, 7)), Premium::DoubleWord);
+        assert_eq!(Premium::at((13, 1)), Premium::DoubleWord);
+        assert_eq!(Premium::at((9, 13)), Premium::TripleLetter);
+        assert_eq!(Premium::at((1, 9)), Premium::TripleLetter);
+        assert_eq!(Premium::at((11, 0)), Premium::DoubleLetter);
+        assert_eq!(Premium::at((8, 12)), Premium::DoubleLetter);
+        assert_eq!(Premium::at((7, 10)), Premium::None);
+
+        let count = |premium: Premium| {
+            (0..BOARD_SIZE * BOARD_SIZE)
+                .filter(|index| Premium::at((index / BOARD_SIZE, index % BOARD_SIZE)) == premium)
+                .count()
+        };
+        assert_eq!(count(Premium::TripleWord), 8);
+        assert_eq!(count(Premium::DoubleWord), 17);
+        assert_eq!(count(Premium::TripleLetter), 12);
+        assert_eq!(count(Premium::DoubleLetter), 24);
+    }
+
+    #[test]
+    fn test_board_from_rows() {
+        let board = board_with("CaT", (7, 7));
+        assert_eq!(board.tile((7, 8)), Some('a'));
+        assert!(board.is_blank((7, 8)));
+        assert!(!board.is_blank((7, 7)));
+        assert_eq!(board.tile((0, 0)), None);
+
+        // Points off the board hold nothing rather than wrapping to the next row
+        let board = board_with("Q", (1, 5));
+        assert_eq!(board.tile((1, 5)), Some('q'));
+        assert_eq!(board.tile((0, 20)), None);
+        assert_eq!(board.tile((20, 0)), None);
+        assert!(!board.is_blank((20, 20)));
+        assert_eq!(Premium::at((20, 0)), Premium::None);
+
+        assert!(matches!(ScrabbleBoard::from_rows(&["..."]), Err(Error::BoardSize { expected: 15, found: 1 })));
+        let mut rows = vec!["..............."; BOARD_SIZE];
+        rows[3] = "....";
+        assert!(matches!(ScrabbleBoard::from_rows(&rows), Err(Error::RaggedGrid { row: 3, .. })));
+        rows[3] = ".......1.......";
+        assert!(matches!(
+            ScrabbleBoard::from_rows(&rows),
+            Err(Error::InvalidSquare { row: 3, column: 7, character: '1' })
+        ));
+    }
+
+    #[test]
+    fn test_opening_moves_cover_the_centre() {
+        let generator = move_generator(&["cat", "act", "at"]);
+        let moves = generator.generate(&ScrabbleBoard::new(), "CAT", &LetterValues::scrabble_english()).unwrap();
+
+        assert!(moves.iter().all(|scrabble_move| {
+            scrabble_move.tiles.iter().any(|tile| tile.point == CENTER)
+        }));
+        // Three squares each way for the three letter words, two for "at"
+        assert_eq!(moves.len(), 2 * (3 + 3 + 2));
+        // C3 A1 T1 doubled by the centre square
+        assert_eq!(moves[0].score, 10);
+        assert_eq!(find_move(&moves, "at", (7, 6), Direction::Right).score, 4);
+    }
+
+    #[test]
+    fn test_blank_scores_nothing() {
+        let generator = move_generator(&["cat"]);
+        let moves = generator.generate(&ScrabbleBoard::new(), "C?T", &LetterValues::scrabble_english()).unwrap();
+
+        let scrabble_move = find_move(&moves, "cat", (7, 5), Direction::Right);
+        assert_eq!(scrabble_move.tiles[1], PlacedTile { point: (7, 6), letter: 'a', is_blank: true });
+        // (C3 + blank + T1) doubled by the centre square
+        assert_eq!(scrabble_move.score, 8);
+    }
+
+    #[test]
+    fn test_moves_scored_with_other_letter_values() {
+        let generator = move_generator(&["cat"]);
+        let moves = generator.generate(&ScrabbleBoard::new(), "CAT", &LetterValues::words_with_friends()).unwrap();
+
+        // C4 A1 T1 doubled by the centre square
+        assert_eq!(moves[0].score, 12);
+    }
+
+    #[test]
+    fn test_bingo_bonus() {
+        let generator = move_generator(&["retains"]);
+        let moves = generator.generate(&ScrabbleBoard::new(), "STAINER", &LetterValues::scrabble_english()).unwrap();
+
+        // Seven one point letters, one of them on the double letter at (7, 3)
+        // or (7, 11), doubled by the centre square, plus the bingo bonus
+        assert_eq!(moves[0].score, (7 + 1) * 2 + BINGO_BONUS);
+        assert_eq!(find_move(&moves, "retains", (7, 4), Direction::Right).score, 7 * 2 + BINGO_BONUS);
+        assert_eq!(moves.len(), 2 * 7);
+    }
+
+    #[test]
+    fn test_generate_rejects_bad_racks() {
+        let generator = move_generator(&["cat"]);
+        let letter_values = LetterValues::scrabble_english();
+
+        assert!(matches!(
+            generator.generate(&ScrabbleBoard::new(), "C1T", &letter_values),
+            Err(Error::NotInAlphabet('1'))
+        ));
+        assert!(matches!(
+            generator.generate(&ScrabbleBoard::new(), "CATSDOGS", &letter_values),
+            Err(Error::InvalidPuzzle(_))
+        ));
+        assert!(generator.generate(&ScrabbleBoard::new(), "CATS???", &letter_values).is_ok());
+    }
+
+    #[test]
+    fn test_moves_with_cross_words() {
+        let generator = move_generator(&["cat", "cats", "ha", "at", "ah"]);
+        let board = board_with("CAT", (7, 7));
+        let moves = generator.generate(&board, "HAS", &LetterValues::scrabble_english()).unwrap();
+
+        // "ha" above "at" forms "ha" and "at" downwards: H on the double letter
+        // at (6, 8) is 8 + A 1 = 9 across, 8 + 1 = 9 for "ha" and 1 + 1 = 2 for "at"
+        let scrabble_move = find_move(&moves, "ha", (6, 8), Direction::Right);
+        assert_eq!(scrabble_move.cross_words, vec!["ha", "at"]);
+        assert_eq!(scrabble_move.score, 20);
+
+        // Hooking an S scores the whole of "cats"
+        let scrabble_move = find_move(&moves, "cats", (7, 7), Direction::Right);
+        assert!(scrabble_move.cross_words.is_empty());
+        assert_eq!(scrabble_move.score, 6);
+
+        // "ah" down through the A of "cat" reuses the tile on the board, with
+        // the H on the double letter at (8, 8)
+        let scrabble_move = find_move(&moves, "ah", (7, 8), Direction::Down);
+        assert_eq!(scrabble_move.tiles, vec![PlacedTile { point: (8, 8), letter: 'h', is_blank: false }]);
+        assert_eq!(scrabble_move.score, 9);
+
+        // Every move touches the tiles already down and forms only real words
+        for scrabble_move in &moves {
+            assert!(scrabble_move.tiles.iter().all(|tile| board.tile(tile.point).is_none()));
+            assert!(scrabble_move.cross_words.iter().all(|word| generator.dictionary.contains(word)));
+        }
+    }
+
+    #[test]
+    fn test_single_tile_is_counted_once() {
+        let generator = move_generator(&["cat", "cats", "as", "at"]);
+        let mut board = board_with("CAT", (7, 7));
+        let moves = generator.generate(&board, "A", &LetterValues::scrabble_english()).unwrap();
+        let mut off_board = find_move(&moves, "at", (6, 9), Direction::Down).clone();
+        off_board.tiles[0].point = (6, 15);
+        assert!(matches!(board.play(&off_board), Err(Error::InvalidPuzzle(_))));
+        assert_eq!(board.tile((6, 9)), None);
+        board.play(&find_move(&moves, "at", (6, 9), Direction::Down).clone()).unwrap();
+        let moves = generator.generate(&board, "S", &LetterValues::scrabble_english()).unwrap();
+
+        // An S at (7, 10) makes "cats" across only
+        assert_eq!(moves.iter().filter(|scrabble_move| scrabble_move.tiles[0].point == (7, 10)).count(), 1);
+        // An S at (6, 10) makes "as" across, next to the A at (6, 9)
+        let scrabble_move = find_move(&moves, "as", (6, 9), Direction::Right);
+        assert!(scrabble_move.cross_words.is_empty());
+    }
+
+    #[test]
+    fn test_generate_with_full_dictionary() {
+        let mut dawg = Dawg::new();
+        TxtFileTrieBuilder::new(SCRABBLE_DICTIONARY_PATH).unwrap().build(&mut dawg).unwrap();
+        let generator = ScrabbleMoveGenerator::new(Rc::new(dawg));
+        let board = board_with("QUIZ", (7, 6));
+
+        let moves = generator.generate(&board, "RETAIN?", &LetterValues::scrabble_english()).unwrap();
+
+        assert!(!moves.is_empty());
+        assert!(moves.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        for scrabble_move in &moves {
+            assert!(generator.dictionary.search(&scrabble_move.word));
+            assert!(scrabble_move.cross_words.iter().all(|word| generator.dictionary.search(word)));
+        }
+    }
+}
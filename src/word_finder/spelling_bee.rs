@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+
+/// Shortest word accepted by the Spelling Bee.
+pub const SPELLING_BEE_MIN_LENGTH: usize = 4;
+/// Extra points for a pangram, a word using every letter of the puzzle.
+pub const PANGRAM_BONUS: u32 = 7;
+
+/// The words found in a Spelling Bee with their points. Pangrams are also
+/// listed in `words`.
+#[derive(Debug, Default)]
+pub struct SpellingBeeResult {
+    pub words: HashMap<String, u32>,
+    pub pangrams: Vec<String>,
+    pub total_score: u32,
+}
+
+/// Points for a word: one for four letters, one per letter above that, and
+/// the bonus on top for a pangram.
+pub fn spelling_bee_score(word: &str, is_pangram: bool) -> u32 {
+    let length = word.chars().count() as u32;
+    let points = if length <= SPELLING_BEE_MIN_LENGTH as u32 { 1 } else { length };
+    if is_pangram { points + PANGRAM_BONUS } else { points }
+}
+
+struct SpellingBeeState {
+    /// The puzzle's distinct letters, the center letter first.
+    letters: Vec<char>,
+    uses: Vec<usize>,
+    current_word: String,
+    result: SpellingBeeResult,
+}
+
+impl SpellingBeeState {
+    fn record_word(&mut self) {
+        let is_pangram = self.uses.iter().all(|&uses| uses > 0);
+        let points = spelling_bee_score(&self.current_word, is_pangram);
+        if is_pangram {
+            self.result.pangrams.push(self.current_word.clone());
+        }
+        self.result.words.insert(self.current_word.clone(), points);
+        self.result.total_score += points;
+    }
+}
+
+/// Finds the words spelled from a set of letters that can each be used any
+/// number of times, as long as the center letter is used at least once.
+pub(crate) struct SpellingBeeWordFinder<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> SpellingBeeWordFinder<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> SpellingBeeWordFinder<T> {
+        SpellingBeeWordFinder { dictionary }
+    }
+
+    /// Solves the puzzle for `center` and the `outer` letters around it.
+    /// Pangrams are returned in alphabetical order.
+    pub(crate) fn search(&self, center: char, outer: &str) -> Result<SpellingBeeResult, Error> {
+        let alphabet = self.dictionary.alphabet();
+        let normalize = |letter: char| alphabet.normalize(letter).ok_or(Error::NotInAlphabet(letter));
+        let center = normalize(center)?;
+        let mut letters = vec![center];
+        for letter in outer.chars() {
+            let letter = normalize(letter)?;
+            if !letters.contains(&letter) {
+                letters.push(letter);
+            }
+        }
+
+        let mut state = SpellingBeeState {
+            uses: vec![0; letters.len()],
+            letters,
+            current_word: String::new(),
+            result: SpellingBeeResult::default(),
+        };
+        self.walk(&self.dictionary.root(), &mut state);
+        state.result.pangrams.sort();
+        Ok(state.result)
+    }
+
+    fn walk<'a>(&'a self, node: &T::Node<'a>, state: &mut SpellingBeeState) {
+        for index in 0..state.letters.len() {
+            let letter = state.letters[index];
+            let Some(next) = self.dictionary.next(node, letter) else {
+                continue;
+            };
+
+            state.current_word.push(letter);
+            state.uses[index] += 1;
+            if state.uses[0] > 0
+                && state.current_word.chars().count() >= SPELLING_BEE_MIN_LENGTH
+                && self.dictionary.is_word(&next)
+            {
+                state.record_word();
+            }
+            self.walk(&next, state);
+            state.uses[index] -= 1;
+            state.current_word.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+    use std::collections::HashSet;
+
+    fn spelling_bee_word_finder(words: &[&str]) -> SpellingBeeWordFinder<HashSet<String>> {
+        SpellingBeeWordFinder::new(test_dictionary(words))
+    }
+
+    #[test]
+    fn test_spelling_bee_score() {
+        assert_eq!(spelling_bee_score("tame", false), 1);
+        assert_eq!(spelling_bee_score("mantle", false), 6);
+        assert_eq!(spelling_bee_score("lamented", true), 15);
+    }
+
+    #[test]
+    fn test_spelling_bee_search() {
+        let finder = spelling_bee_word_finder(&[
+            "tame", "team", "meat", "mate", "amen", "mantle", "lamented", "dental", "matted", "tat", "lame", "deal",
+        ]);
+        let result = finder.search('M', "ADELNT").unwrap();
+
+        assert_eq!(result.pangrams, vec!["lamented"]);
+        // Letters repeat as often as needed, but "dental" and "deal" lack the M
+        assert_eq!(result.words.len(), 9);
+        assert!(!result.words.contains_key("dental"));
+        assert!(!result.words.contains_key("tat"));
+        assert_eq!(result.words["matted"], 6);
+        assert_eq!(result.words["lamented"], 15);
+        // Six four letter words, "mantle", "matted" and the pangram
+        assert_eq!(result.total_score, 6 + 6 + 6 + 15);
+        assert_eq!(result.total_score, result.words.values().sum::<u32>());
+    }
+
+    #[test]
+    fn test_spelling_bee_invalid_letter() {
+        let finder = spelling_bee_word_finder(&["tame"]);
+        assert!(matches!(finder.search('m', "ade1nt"), Err(Error::NotInAlphabet('1'))));
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+
+/// Which ladders a word ladder search returns and the steps they may take.
+#[derive(Clone, Debug, Default)]
+pub struct WordLadderOptions {
+    /// Returns every shortest ladder instead of only the first in
+    /// alphabetical order.
+    pub all_ladders: bool,
+    /// Also allows steps adding or removing a single letter anywhere in the
+    /// word, so the start and target may differ in length. Without it words
+    /// of different lengths are an error.
+    pub add_or_remove: bool,
+}
+
+impl WordLadderOptions {
+    pub fn new(all_ladders: bool, add_or_remove: bool) -> WordLadderOptions {
+        WordLadderOptions {
+            all_ladders,
+            add_or_remove,
+        }
+    }
+}
+
+/// Finds the shortest chains of words from one word to another, each step
+/// changing a single letter.
+pub(crate) struct WordLadderFinder<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> WordLadderFinder<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> WordLadderFinder<T> {
+        WordLadderFinder { dictionary }
+    }
+
+    /// Returns the shortest ladders from `start` to `target` in alphabetical
+    /// order, or none if the two words are not connected.
+    pub(crate) fn search(&self, start: &str, target: &str, options: &WordLadderOptions) -> Result<Vec<Vec<String>>, Error> {
+        let start = self.read_word(start)?;
+        let target = self.read_word(target)?;
+        if !options.add_or_remove && start.chars().count() != target.chars().count() {
+            return Err(Error::InvalidPuzzle(format!(
+                "{} and {} differ in length, which needs steps adding or removing letters",
+                start, target
+            )));
+        }
+
+        let mut parents: HashMap<String, Vec<String>> = HashMap::from([(start.clone(), Vec::new())]);
+        let mut frontier = vec![start.clone()];
+        while !frontier.is_empty() && !parents.contains_key(&target) {
+            frontier.sort();
+            let mut next_frontier = Vec::new();
+            let mut layer: HashMap<String, Vec<String>> = HashMap::new();
+            for word in &frontier {
+                for neighbour in self.neighbours(word, options.add_or_remove) {
+                    if parents.contains_key(&neighbour) {
+                        continue;
+                    }
+                    let word_parents = layer.entry(neighbour.clone()).or_default();
+                    if word_parents.is_empty() {
+                        next_frontier.push(neighbour);
+                    }
+                    word_parents.push(word.clone());
+                }
+            }
+            parents.extend(layer);
+            frontier = next_frontier;
+        }
+
+        if !parents.contains_key(&target) {
+            return Ok(Vec::new());
+        }
+        if !options.all_ladders {
+            return Ok(vec![first_ladder(&start, &target, &parents)]);
+        }
+        let mut ladders = Vec::new();
+        read_ladders(&target, &parents, &mut vec![target.clone()], &mut ladders);
+        ladders.sort();
+        Ok(ladders)
+    }
+
+    fn read_word(&self, word: &str) -> Result<String, Error> {
+        let alphabet = self.dictionary.alphabet();
+        let word: String = word
+            .chars()
+            .map(|letter| alphabet.normalize(letter).ok_or(Error::NotInAlphabet(letter)))
+            .collect::<Result<String, Error>>()?;
+        if !self.dictionary.search(&word) {
+            return Err(Error::InvalidPuzzle(format!("{} is not in the dictionary", word)));
+        }
+        Ok(word)
+    }
+
+    /// The words one step away from `word`, in alphabetical order. Walks the
+    /// trie along `word` once, branching off at each position to every letter
+    /// the trie allows there and following the rest of the word from it.
+    pub(crate) fn neighbours(&self, word: &str, add_or_remove: bool) -> Vec<String> {
+        let letters: Vec<char> = word.chars().collect();
+        let mut neighbours = BTreeSet::new();
+        let mut node = self.dictionary.root();
+
+        for position in 0..=letters.len() {
+            let (prefix, rest) = letters.split_at(position);
+            for &letter in self.dictionary.alphabet().letters() {
+                let Some(next) = self.dictionary.next(&node, letter) else {
+                    continue;
+                };
+                if rest.first().is_some_and(|&replaced| replaced != letter) {
+                    self.insert_if_word(&next, prefix, Some(letter), &rest[1..], &mut neighbours);
+                }
+                if add_or_remove {
+                    self.insert_if_word(&next, prefix, Some(letter), rest, &mut neighbours);
+                }
+            }
+            let Some((&current, suffix)) = rest.split_first() else {
+                break;
+            };
+            if add_or_remove {
+                self.insert_if_word(&node, prefix, None, suffix, &mut neighbours);
+            }
+            node = match self.dictionary.next(&node, current) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        neighbours.into_iter().collect()
+    }
+
+    /// Follows `suffix` from `node`, which spells `prefix` and `letter`, and
+    /// keeps the result if it is a word.
+    fn insert_if_word<'a>(
+        &'a self,
+        node: &T::Node<'a>,
+        prefix: &[char],
+        letter: Option<char>,
+        suffix: &[char],
+        neighbours: &mut BTreeSet<String>,
+    ) {
+        let mut node = node.clone();
+        for &next_letter in suffix {
+            node = match self.dictionary.next(&node, next_letter) {
+                Some(next) => next,
+                None => return,
+            };
+        }
+        if self.dictionary.is_word(&node) {
+            neighbours.insert(prefix.iter().chain(letter.iter()).chain(suffix).collect());
+        }
+    }
+}
+
+/// Reads every ladder back from `word` to the start.
+fn read_ladders(word: &str, parents: &HashMap<String, Vec<String>>, ladder: &mut Vec<String>, ladders: &mut Vec<Vec<String>>) {
+    let word_parents = &parents[word];
+    if word_parents.is_empty() {
+        ladders.push(ladder.iter().rev().cloned().collect());
+        return;
+    }
+    for parent in word_parents {
+        ladder.push(parent.clone());
+        read_ladders(parent, parents, ladder, ladders);
+        ladder.pop();
+    }
+}
+
+/// The alphabetically first of the shortest ladders, built from the start by
+/// always stepping to the smallest word that still leads to `target`.
+fn first_ladder(start: &str, target: &str, parents: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let mut leads_to_target: HashSet<&str> = HashSet::new();
+    let mut stack = vec![target];
+    while let Some(word) = stack.pop() {
+        if leads_to_target.insert(word) {
+            stack.extend(parents[word].iter().map(String::as_str));
+        }
+    }
+
+    let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+    for &word in &leads_to_target {
+        for parent in &parents[word] {
+            children.entry(parent.as_str()).or_default().push(word);
+        }
+    }
+    let mut ladder = vec![start.to_string()];
+    let mut current = start;
+    while current != target {
+        current = children[current].iter().min().unwrap();
+        ladder.push(current.to_string());
+    }
+    ladder
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+    use std::collections::HashSet;
+
+    fn word_ladder_finder(words: &[&str]) -> WordLadderFinder<HashSet<String>> {
+        WordLadderFinder::new(test_dictionary(words))
+    }
+
+    fn ladders(ladders: &[&[&str]]) -> Vec<Vec<String>> {
+        ladders
+            .iter()
+            .map(|ladder| ladder.iter().map(|word| word.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let finder = word_ladder_finder(&["cat", "car", "cart", "care", "scar", "bar", "cot", "at", "coat"]);
+
+        assert_eq!(finder.neighbours("cat", false), vec!["car", "cot"]);
+        assert_eq!(finder.neighbours("cat", true), vec!["at", "car", "cart", "coat", "cot"]);
+        assert_eq!(finder.neighbours("cart", true), vec!["car", "care", "cat"]);
+        assert_eq!(finder.neighbours("car", true), vec!["bar", "care", "cart", "cat", "scar"]);
+    }
+
+    #[test]
+    fn test_shortest_ladders() {
+        let finder = word_ladder_finder(&["cold", "cord", "card", "ward", "warm", "word", "worm", "wore"]);
+
+        let all = finder.search("COLD", "warm", &WordLadderOptions::new(true, false)).unwrap();
+        assert_eq!(
+            all,
+            ladders(&[
+                &["cold", "cord", "card", "ward", "warm"],
+                &["cold", "cord", "word", "ward", "warm"],
+                &["cold", "cord", "word", "worm", "warm"],
+            ])
+        );
+        let first = finder.search("cold", "warm", &WordLadderOptions::default()).unwrap();
+        assert_eq!(first, ladders(&[&["cold", "cord", "card", "ward", "warm"]]));
+
+        assert_eq!(finder.search("cold", "cold", &WordLadderOptions::default()).unwrap(), ladders(&[&["cold"]]));
+    }
+
+    #[test]
+    fn test_first_ladder_is_the_smallest() {
+        // Reading back from the target would pick "abc" before "acb", which is
+        // on a ladder starting with the larger "aba"
+        let finder = word_ladder_finder(&["aaa", "aab", "aba", "abc", "acb", "acc"]);
+
+        let all = finder.search("aaa", "acc", &WordLadderOptions::new(true, false)).unwrap();
+        assert_eq!(all, ladders(&[&["aaa", "aab", "acb", "acc"], &["aaa", "aba", "abc", "acc"]]));
+        let first = finder.search("aaa", "acc", &WordLadderOptions::default()).unwrap();
+        assert_eq!(first, all[..1]);
+    }
+
+    #[test]
+    fn test_ladders_adding_and_removing_letters() {
+        let finder = word_ladder_finder(&["cat", "car", "cart", "care", "bar", "bare"]);
+
+        assert!(matches!(finder.search("cat", "bare", &WordLadderOptions::new(true, false)), Err(Error::InvalidPuzzle(_))));
+        let all = finder.search("cat", "bare", &WordLadderOptions::new(true, true)).unwrap();
+        assert_eq!(
+            all,
+            ladders(&[
+                &["cat", "car", "bar", "bare"],
+                &["cat", "car", "care", "bare"],
+                &["cat", "cart", "care", "bare"],
+            ])
+        );
+    }
+
+    #[test]
+    fn test_ladder_errors() {
+        let finder = word_ladder_finder(&["cat", "cot"]);
+
+        assert!(matches!(finder.search("cat", "dog", &WordLadderOptions::default()), Err(Error::InvalidPuzzle(_))));
+        assert!(matches!(finder.search("c4t", "cot", &WordLadderOptions::default()), Err(Error::NotInAlphabet('4'))));
+
+        let finder = word_ladder_finder(&["cat", "cart", "dog"]);
+        assert!(finder.search("cat", "dog", &WordLadderOptions::default()).unwrap().is_empty());
+        assert!(matches!(finder.search("cat", "cart", &WordLadderOptions::default()), Err(Error::InvalidPuzzle(_))));
+        let found = finder.search("cat", "cart", &WordLadderOptions::new(false, true)).unwrap();
+        assert_eq!(found, ladders(&[&["cat", "cart"]]));
+    }
+}
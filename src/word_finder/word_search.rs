@@ -0,0 +1,180 @@
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::word_finder::matrix::{Direction, Matrix, Point};
+
+/// A word read in a straight line from `start` towards `direction`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WordSearchMatch {
+    pub word: String,
+    pub start: Point,
+    pub direction: Direction,
+    /// Number of cells the word covers.
+    pub length: usize,
+}
+
+/// Finds words laid out in a straight line, as in a classic word search puzzle.
+pub(crate) struct WordSearchWordFinder<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> WordSearchWordFinder<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> WordSearchWordFinder<T> {
+        WordSearchWordFinder { dictionary }
+    }
+
+    /// Returns every word of at least `min_length` letters, ordered by start
+    /// point, then direction, then length. A word filling a single cell reads
+    /// the same every way and is reported once, towards the first direction.
+    pub(crate) fn search(&self, matrix: &Matrix, min_length: usize) -> Vec<WordSearchMatch> {
+        let mut matches = Vec::new();
+
+        for index in 0..matrix.get_capacity() {
+            let start = matrix.get_point_from_index(index);
+            for (index, direction) in Direction::ALL.into_iter().enumerate() {
+                let min_cells = if index == 0 { 1 } else { 2 };
+                self.read_line(matrix, start, direction, min_length, min_cells, &mut matches);
+            }
+        }
+        matches
+    }
+
+    /// Follows `direction` from `start` for as long as the letters read so far
+    /// are the prefix of a word, reporting words covering at least `min_cells`.
+    fn read_line(
+        &self,
+        matrix: &Matrix,
+        start: Point,
+        direction: Direction,
+        min_length: usize,
+        min_cells: usize,
+        matches: &mut Vec<WordSearchMatch>,
+    ) {
+        let alphabet = self.dictionary.alphabet();
+        let mut node = self.dictionary.root();
+        let mut word = String::new();
+        let mut point = Some(start);
+        let mut length = 0;
+
+        while let Some(current) = point {
+            for letter in matrix.get_tile(current).chars() {
+                let Some(letter) = alphabet.normalize(letter) else {
+                    return;
+                };
+                let Some(next) = self.dictionary.next(&node, letter) else {
+                    return;
+                };
+                node = next;
+                word.push(letter);
+            }
+            length += 1;
+
+            if self.dictionary.is_word(&node) && word.chars().count() >= min_length && length >= min_cells {
+                matches.push(WordSearchMatch {
+                    word: word.clone(),
+                    start,
+                    direction,
+                    length,
+                });
+            }
+            point = matrix.next(current, &direction);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+    use std::collections::HashSet;
+    use crate::word_finder::matrix::Direction::{Down, Left, LowerRight, Right, UpperLeft};
+
+    #[test]
+    fn test_word_search_finds_straight_lines_only() {
+        let matrix = Matrix::new(&[
+            &['c', 'a', 't', 'x'],
+            &['o', 'd', 'o', 'g'],
+            &['w', 'x', 'g', 'x'],
+            &['x', 'x', 'x', 's'],
+        ]).unwrap();
+        // "cot" would need a bend, "dog" reads left to right, "tac" right to left
+        let dictionary = test_dictionary(&["cat", "tac", "cow", "dog", "cod", "cot", "cogs", "ado"]);
+        let word_search_word_finder = WordSearchWordFinder::new(dictionary);
+
+        let matches = word_search_word_finder.search(&matrix, 3);
+        let words: HashSet<&str> = matches.iter().map(|found| found.word.as_str()).collect();
+
+        assert_eq!(words, HashSet::from(["cat", "tac", "cow", "dog"]));
+        assert!(matches.contains(&WordSearchMatch {
+            word: "cow".to_string(),
+            start: (0, 0),
+            direction: Down,
+            length: 3,
+        }));
+        assert!(matches.contains(&WordSearchMatch {
+            word: "tac".to_string(),
+            start: (0, 2),
+            direction: Left,
+            length: 3,
+        }));
+        assert!(matches.contains(&WordSearchMatch {
+            word: "dog".to_string(),
+            start: (1, 1),
+            direction: Right,
+            length: 3,
+        }));
+    }
+
+    #[test]
+    fn test_word_search_diagonals_and_min_length() {
+        let matrix = Matrix::new(&[&['s', 'x', 'x'], &['x', 'e', 'x'], &['x', 'x', 'a']]).unwrap();
+        let dictionary = test_dictionary(&["sea", "se", "aes"]);
+        let word_search_word_finder = WordSearchWordFinder::new(dictionary);
+
+        let matches = word_search_word_finder.search(&matrix, 3);
+
+        assert_eq!(
+            matches,
+            vec![
+                WordSearchMatch {
+                    word: "sea".to_string(),
+                    start: (0, 0),
+                    direction: LowerRight,
+                    length: 3,
+                },
+                WordSearchMatch {
+                    word: "aes".to_string(),
+                    start: (2, 2),
+                    direction: UpperLeft,
+                    length: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_word_search_multi_letter_tiles() {
+        let matrix = Matrix::from_tiles(&[&["Qu", "I", "T"]]).unwrap();
+        let word_search_word_finder = WordSearchWordFinder::new(test_dictionary(&["quit"]));
+
+        let matches = word_search_word_finder.search(&matrix, 3);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].length, 3);
+        assert_eq!(matches[0].word, "quit");
+    }
+
+    #[test]
+    fn test_word_search_single_cell_words() {
+        let matrix = Matrix::from_tiles(&[&["a", "Qu"], &["i", "x"]]).unwrap();
+        let word_search_word_finder = WordSearchWordFinder::new(test_dictionary(&["a", "qu", "ai"]));
+
+        let matches = word_search_word_finder.search(&matrix, 1);
+        let found: Vec<(&str, Point, Direction)> =
+            matches.iter().map(|found| (found.word.as_str(), found.start, found.direction)).collect();
+
+        assert_eq!(
+            found,
+            vec![("a", (0, 0), Direction::Up), ("ai", (0, 0), Down), ("qu", (0, 1), Direction::Up)]
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use crate::dictionaries::Dictionary;
+use crate::error::Error;
+use crate::word_finder::filters::Length;
+use crate::word_finder::{BasicWordFilters, WordFilters};
+
+/// Guessed words tried when there are at most this many candidates left.
+/// Above it only candidates are tried, as a guess that cannot win rarely
+/// splits a large set much better than one that can.
+const ALL_GUESSES_LIMIT: usize = 100;
+/// Candidates a guess is scored against. Larger sets are sampled evenly.
+const ANSWER_SAMPLE: usize = 300;
+
+/// The colour a letter of a guess is shown in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LetterFeedback {
+    /// Green: the letter is in the answer at this position.
+    Correct,
+    /// Yellow: the letter is in the answer at another position.
+    Present,
+    /// Grey: the answer has no more of this letter.
+    Absent,
+}
+
+/// A guessed word along with the feedback it got.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Guess {
+    pub word: String,
+    pub feedback: Vec<LetterFeedback>,
+}
+
+/// The words still possible after a series of guesses, and the guess
+/// expected to narrow them down the most.
+#[derive(Clone, Debug, Default)]
+pub struct WordleResult {
+    pub candidates: Vec<String>,
+    pub suggestion: Option<String>,
+}
+
+impl LetterFeedback {
+    /// The feedback for `guess` when the answer is `answer`. A repeated letter
+    /// is only marked present as many times as the answer has it unmatched.
+    pub fn compare(guess: &str, answer: &str) -> Vec<LetterFeedback> {
+        let guess: Vec<char> = guess.chars().collect();
+        let answer: Vec<char> = answer.chars().collect();
+        compare_letters(&guess, &answer)
+    }
+}
+
+fn compare_letters(guess: &[char], answer: &[char]) -> Vec<LetterFeedback> {
+    let mut feedback = vec![LetterFeedback::Absent; guess.len()];
+    let mut unmatched = Vec::new();
+    for (position, &letter) in answer.iter().enumerate() {
+        if guess.get(position) == Some(&letter) {
+            feedback[position] = LetterFeedback::Correct;
+        } else {
+            unmatched.push(letter);
+        }
+    }
+
+    for (position, letter) in guess.iter().enumerate() {
+        if feedback[position] == LetterFeedback::Correct {
+            continue;
+        }
+        if let Some(index) = unmatched.iter().position(|unmatched| unmatched == letter) {
+            unmatched.swap_remove(index);
+            feedback[position] = LetterFeedback::Present;
+        }
+    }
+    feedback
+}
+
+impl Guess {
+    /// Reads feedback written with one character per letter: `g` for green,
+    /// `y` for yellow and `.` or `x` for grey, so "crane" answered by "cater"
+    /// is `Guess::new("crane", "gyy.y")`.
+    pub fn new(word: &str, feedback: &str) -> Result<Guess, Error> {
+        let word = word.to_lowercase();
+        let feedback = feedback
+            .chars()
+            .map(|mark| match mark.to_ascii_lowercase() {
+                'g' => Ok(LetterFeedback::Correct),
+                'y' => Ok(LetterFeedback::Present),
+                '.' | 'x' => Ok(LetterFeedback::Absent),
+                mark => Err(Error::InvalidFeedback(format!("unknown mark {} for {}", mark, word))),
+            })
+            .collect::<Result<Vec<LetterFeedback>, Error>>()?;
+
+        if feedback.len() != word.chars().count() {
+            return Err(Error::InvalidFeedback(format!(
+                "{} marks for the {} letters of {}",
+                feedback.len(),
+                word.chars().count(),
+                word
+            )));
+        }
+        Ok(Guess { word, feedback })
+    }
+}
+
+/// Passes the words that would have given the same feedback to every guess.
+/// Prefixes are pruned on the letters each position rules in or out, and on
+/// letters the answer is known to have fewer of.
+#[derive(Clone, Debug)]
+pub struct WordleFeedback {
+    guesses: Vec<(Vec<char>, Vec<LetterFeedback>)>,
+    required: Vec<Option<char>>,
+    forbidden: Vec<Vec<char>>,
+    max_counts: HashMap<char, usize>,
+}
+
+impl WordleFeedback {
+    pub fn new(guesses: &[Guess]) -> WordleFeedback {
+        let mut filter = WordleFeedback {
+            guesses: Vec::new(),
+            required: Vec::new(),
+            forbidden: Vec::new(),
+            max_counts: HashMap::new(),
+        };
+
+        for guess in guesses {
+            let letters: Vec<char> = guess.word.chars().collect();
+            if filter.required.len() < letters.len() {
+                filter.required.resize(letters.len(), None);
+                filter.forbidden.resize(letters.len(), Vec::new());
+            }
+
+            for (position, (&letter, &feedback)) in letters.iter().zip(&guess.feedback).enumerate() {
+                match feedback {
+                    LetterFeedback::Correct => filter.required[position] = Some(letter),
+                    _ => filter.forbidden[position].push(letter),
+                }
+                if feedback == LetterFeedback::Absent {
+                    // A grey letter caps the count at its green and yellow copies
+                    let found = letters
+                        .iter()
+                        .zip(&guess.feedback)
+                        .filter(|&(&other, &feedback)| other == letter && feedback != LetterFeedback::Absent)
+                        .count();
+                    let max_count = filter.max_counts.entry(letter).or_insert(found);
+                    *max_count = (*max_count).min(found);
+                }
+            }
+            filter.guesses.push((letters, guess.feedback.clone()));
+        }
+        filter
+    }
+}
+
+impl WordFilters for WordleFeedback {
+    fn passes_filters(&self, word: &str) -> bool {
+        let letters: Vec<char> = word.chars().collect();
+        self.guesses
+            .iter()
+            .all(|(guess, feedback)| guess.len() == letters.len() && compare_letters(guess, &letters) == *feedback)
+    }
+
+    fn may_match_prefix(&self, prefix: &str) -> bool {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for (position, letter) in prefix.chars().enumerate() {
+            if self.required.get(position).is_some_and(|required| required.is_some_and(|required| required != letter)) {
+                return false;
+            }
+            if self.forbidden.get(position).is_some_and(|forbidden| forbidden.contains(&letter)) {
+                return false;
+            }
+            let count = counts.entry(letter).or_insert(0);
+            *count += 1;
+            if self.max_counts.get(&letter).is_some_and(|&max_count| *count > max_count) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Solves Wordle-style games, where each guess is answered with which of its
+/// letters are in the hidden word and where.
+pub(crate) struct WordleSolver<T: Dictionary> {
+    dictionary: Rc<T>,
+}
+
+impl<T: Dictionary> WordleSolver<T> {
+    pub(crate) fn new(dictionary: Rc<T>) -> WordleSolver<T> {
+        WordleSolver { dictionary }
+    }
+
+    pub(crate) fn solve(&self, length: usize, guesses: &[Guess]) -> WordleResult {
+        let candidates = self.words(length, &WordleFeedback::new(guesses));
+        let suggestion = self.suggest(length, &candidates);
+        WordleResult { candidates, suggestion }
+    }
+
+    /// The dictionary words of `length` letters passing `filters`, in
+    /// alphabet order.
+    pub(crate) fn words<F: WordFilters>(&self, length: usize, filters: &F) -> Vec<String> {
+        let filters = Length::exactly(length).and(filters);
+        let mut words = Vec::new();
+        self.collect(&self.dictionary.root(), length, &filters, &mut String::new(), &mut words);
+        words
+    }
+
+    fn collect<'a, F: WordFilters>(
+        &'a self,
+        node: &T::Node<'a>,
+        length: usize,
+        filters: &F,
+        current_word: &mut String,
+        words: &mut Vec<String>,
+    ) {
+        if current_word.chars().count() == length {
+            if self.dictionary.is_word(node) && filters.passes_filters(current_word) {
+                words.push(current_word.clone());
+            }
+            return;
+        }
+
+        for &letter in self.dictionary.alphabet().letters() {
+            if let Some(next) = self.dictionary.next(node, letter) {
+                current_word.push(letter);
+                if filters.may_match_prefix(current_word) {
+                    self.collect(&next, length, filters, current_word, words);
+                }
+                current_word.pop();
+            }
+        }
+    }
+
+    /// The guess whose feedback is most uncertain over the candidates, which
+    /// is the guess expected to leave the fewest of them. Ties go to
+    /// candidates, as they might win outright.
+    fn suggest(&self, length: usize, candidates: &[String]) -> Option<String> {
+        if candidates.len() <= 2 {
+            return candidates.first().cloned();
+        }
+
+        let answers: Vec<Vec<char>> = candidates
+            .iter()
+            .step_by(candidates.len().div_ceil(ANSWER_SAMPLE))
+            .map(|candidate| candidate.chars().collect())
+            .collect();
+        let guesses = if candidates.len() <= ALL_GUESSES_LIMIT {
+            self.words(length, &BasicWordFilters::default())
+        } else {
+            candidates.to_vec()
+        };
+
+        let candidate_set: HashSet<&String> = candidates.iter().collect();
+        let mut best: Option<(f64, bool, &String)> = None;
+        for guess in &guesses {
+            let entropy = entropy(guess, &answers);
+            let is_candidate = candidate_set.contains(guess);
+            if best.is_none_or(|(best_entropy, best_is_candidate, _)| {
+                entropy > best_entropy + f64::EPSILON
+                    || ((entropy - best_entropy).abs() <= f64::EPSILON && is_candidate && !best_is_candidate)
+            }) {
+                best = Some((entropy, is_candidate, guess));
+            }
+        }
+        best.map(|(_, _, guess)| guess.clone())
+    }
+}
+
+/// The entropy, in bits, of the feedback `guess` gets over `answers`.
+fn entropy(guess: &str, answers: &[Vec<char>]) -> f64 {
+    let guess: Vec<char> = guess.chars().collect();
+    let mut partitions: HashMap<Vec<LetterFeedback>, usize> = HashMap::new();
+    for answer in answers {
+        *partitions.entry(compare_letters(&guess, answer)).or_insert(0) += 1;
+    }
+
+    let total = answers.len() as f64;
+    partitions
+        .values()
+        .map(|&count| {
+            let probability = count as f64 / total;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word_finder::test_dictionary;
+    use crate::dictionaries::tries::Trie;
+    use crate::dictionaries::tries::dawg::Dawg;
+    use crate::dictionaries::tries::trie_builder::{TrieBuilder, TxtFileTrieBuilder};
+    use crate::SCRABBLE_DICTIONARY_PATH;
+    use LetterFeedback::{Absent, Correct, Present};
+
+    fn wordle_solver(words: &[&str]) -> WordleSolver<HashSet<String>> {
+        WordleSolver::new(test_dictionary(words))
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(LetterFeedback::compare("crane", "cater"), vec![Correct, Present, Present, Absent, Present]);
+        // Only one of the guessed Es is yellow, as the answer has a single E
+        assert_eq!(LetterFeedback::compare("geese", "those"), vec![Absent, Absent, Absent, Correct, Correct]);
+        assert_eq!(LetterFeedback::compare("eerie", "spent"), vec![Present, Absent, Absent, Absent, Absent]);
+        assert_eq!(LetterFeedback::compare("speed", "abide"), vec![Absent, Absent, Present, Absent, Present]);
+    }
+
+    #[test]
+    fn test_parse_guess() {
+        let guess = Guess::new("CRANE", "Gyy.x").unwrap();
+        assert_eq!(guess.word, "crane");
+        assert_eq!(guess.feedback, vec![Correct, Present, Present, Absent, Absent]);
+
+        assert!(matches!(Guess::new("crane", "gyy"), Err(Error::InvalidFeedback(_))));
+        assert!(matches!(Guess::new("crane", "gyyzz"), Err(Error::InvalidFeedback(_))));
+    }
+
+    #[test]
+    fn test_wordle_feedback_filter() {
+        let filter = WordleFeedback::new(&[Guess::new("geese", "...gg").unwrap()]);
+
+        assert!(filter.passes_filters("those"));
+        assert!(filter.passes_filters("prose"));
+        assert!(!filter.passes_filters("elope"));
+        // The grey Es mean the answer has a single E
+        assert!(!filter.may_match_prefix("ee"));
+        assert!(!filter.may_match_prefix("g"));
+        assert!(filter.may_match_prefix("tho"));
+        assert!(!filter.may_match_prefix("thosa"));
+    }
+
+    #[test]
+    fn test_solve() {
+        let solver = wordle_solver(&["cater", "crate", "trace", "react", "caret", "crane", "tales", "cat"]);
+        let result = solver.solve(5, &[Guess::new("crane", "gyy.y").unwrap()]);
+
+        assert_eq!(result.candidates, vec!["caret", "cater"]);
+        assert_eq!(result.suggestion, Some("caret".to_string()));
+
+        let result = solver.solve(5, &[]);
+        assert_eq!(result.candidates.len(), 7);
+        assert!(solver.solve(3, &[Guess::new("crane", "ggggg").unwrap()]).candidates.is_empty());
+    }
+
+    #[test]
+    fn test_suggestion_splits_candidates() {
+        // Any of the candidates leaves the other three looking the same, while
+        // "climb" tells all four apart
+        let solver = wordle_solver(&["batch", "catch", "hatch", "match", "climb"]);
+        let candidates = solver.words(5, &WordleFeedback::new(&[Guess::new("latch", ".gggg").unwrap()]));
+
+        assert_eq!(candidates, vec!["batch", "catch", "hatch", "match"]);
+        assert_eq!(solver.suggest(5, &candidates), Some("climb".to_string()));
+    }
+
+    #[test]
+    fn test_solve_with_full_dictionary() {
+        let mut dawg = Dawg::new();
+        TxtFileTrieBuilder::new(SCRABBLE_DICTIONARY_PATH).unwrap().build(&mut dawg).unwrap();
+        let solver = WordleSolver::new(Rc::new(dawg));
+        let guesses = [Guess::new("crane", ".y..y").unwrap(), Guess::new("those", "...yy").unwrap()];
+
+        let result = solver.solve(5, &guesses);
+
+        assert!(result.candidates.contains(&"users".to_string()));
+        assert!(result.candidates.iter().all(|candidate| {
+            guesses
+                .iter()
+                .all(|guess| LetterFeedback::compare(&guess.word, candidate) == guess.feedback)
+        }));
+        assert!(result.suggestion.is_some());
+    }
+}